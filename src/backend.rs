@@ -0,0 +1,390 @@
+use crate::error::{MuvError, Result};
+use crate::requirements::{self, OutdatedPackage};
+use crate::utils;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The available backends, selected with `--backend` or `MUV_BACKEND`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// uv (`uv venv`, `uv pip`)
+    #[default]
+    Uv,
+    /// The standard library's `venv` and the environment's own pip, for hosts without uv
+    Pip,
+}
+
+pub fn backend_for(kind: BackendKind) -> Box<dyn EnvBackend> {
+    match kind {
+        BackendKind::Uv => Box::new(UvBackend::default()),
+        BackendKind::Pip => Box::new(PipBackend),
+    }
+}
+
+/// What to install into an environment, in backend-neutral terms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstallRequest {
+    /// Requirements files (`-r`).
+    pub requirements: Vec<String>,
+    /// Local projects to install in editable mode (`-e`).
+    pub editables: Vec<String>,
+    /// Requirement specifiers or paths.
+    pub packages: Vec<String>,
+    /// Constraints files (`-c`).
+    pub constraints: Vec<String>,
+    /// Overrides files (`--override`).
+    pub overrides: Vec<String>,
+    pub no_deps: bool,
+    pub upgrade: bool,
+    /// Index options (`--index-url`, `--extra-index-url`, `--find-links`).
+    pub index_args: Vec<String>,
+}
+
+impl InstallRequest {
+    pub fn packages(packages: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            packages: packages.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// The operations muv needs from a tool that builds and manages virtual environments.
+/// Command handlers only talk to this trait, so the tool can be swapped out (or faked
+/// in tests). Backends must be `Send + Sync`: `muv run --all -j N` uses one from several
+/// threads.
+pub trait EnvBackend: Send + Sync {
+    /// Short name for messages, e.g. `uv`.
+    fn name(&self) -> &str;
+
+    /// Create a virtual environment at `env_path`. `python` is a version, an
+    /// interpreter name or path, or a version specifier.
+    fn create_venv(&self, env_path: &Path, python: Option<&str>) -> Result<()>;
+
+    fn install(&self, env_path: &Path, request: &InstallRequest) -> Result<()>;
+
+    /// Uninstall packages, given as names and/or requirements files.
+    fn uninstall(
+        &self,
+        env_path: &Path,
+        packages: &[String],
+        requirements: &[String],
+    ) -> Result<()>;
+
+    /// Installed packages in requirements format.
+    fn freeze(&self, env_path: &Path) -> Result<String>;
+
+    /// Installed packages with a newer version available.
+    fn list_outdated(&self, env_path: &Path, index_args: &[String])
+    -> Result<Vec<OutdatedPackage>>;
+
+    /// Resolve `input` (a requirements or pyproject file) into pinned requirements at `output`.
+    // Part of the backend interface, though no command compiles yet.
+    #[allow(dead_code)]
+    fn compile(&self, input: &Path, output: &Path, index_args: &[String]) -> Result<()>;
+
+    /// Make the environment contain exactly the packages pinned in `requirements`.
+    fn sync(&self, env_path: &Path, requirements: &Path) -> Result<()>;
+}
+
+/// The default backend, driving `uv venv` and `uv pip`. Whether uv is installed is
+/// only checked the first time it is needed, so commands that never touch a backend
+/// work without it.
+#[derive(Debug, Default)]
+pub struct UvBackend {
+    available: OnceLock<()>,
+}
+
+impl UvBackend {
+    fn ensure_available(&self) -> Result<()> {
+        if self.available.get().is_none() {
+            utils::check_uv_exists()?;
+            let _ = self.available.set(());
+        }
+        Ok(())
+    }
+
+    fn run(&self, args: &[&str]) -> Result<()> {
+        self.ensure_available()?;
+        utils::run_uv_command(args, None, vec![])
+    }
+
+    fn run_in_env(&self, env_path: &Path, args: &[&str]) -> Result<()> {
+        self.ensure_available()?;
+        utils::run_uv_command(args, None, vec![(utils::ACTIVE_ENV_VAR, env_path)])
+    }
+
+    fn output_in_env(&self, env_path: &Path, args: &[&str]) -> Result<String> {
+        self.ensure_available()?;
+        utils::get_command_output("uv", args, None, vec![(utils::ACTIVE_ENV_VAR, env_path)])
+    }
+}
+
+impl EnvBackend for UvBackend {
+    fn name(&self) -> &str {
+        "uv"
+    }
+
+    fn create_venv(&self, env_path: &Path, python: Option<&str>) -> Result<()> {
+        let env_str = env_path
+            .to_str()
+            .ok_or_else(|| MuvError::Anyhow(anyhow::anyhow!("Invalid path")))?;
+        let mut args = vec!["venv"];
+        if let Some(python) = python {
+            args.extend(["--python", python]);
+        }
+        args.push(env_str);
+        self.run(&args)
+    }
+
+    fn install(&self, env_path: &Path, request: &InstallRequest) -> Result<()> {
+        let mut args = vec!["pip", "install"];
+        if request.upgrade {
+            args.push("--upgrade");
+        }
+        for file in &request.requirements {
+            args.extend(["-r", file.as_str()]);
+        }
+        for path in &request.editables {
+            args.extend(["-e", path.as_str()]);
+        }
+        args.extend(request.packages.iter().map(String::as_str));
+        // Options that only affect resolution.
+        for file in &request.constraints {
+            args.extend(["-c", file.as_str()]);
+        }
+        for file in &request.overrides {
+            args.extend(["--override", file.as_str()]);
+        }
+        if request.no_deps {
+            args.push("--no-deps");
+        }
+        args.extend(request.index_args.iter().map(String::as_str));
+        self.run_in_env(env_path, &args)
+    }
+
+    fn uninstall(
+        &self,
+        env_path: &Path,
+        packages: &[String],
+        requirements: &[String],
+    ) -> Result<()> {
+        let mut args = vec!["pip", "uninstall"];
+        for file in requirements {
+            args.extend(["-r", file.as_str()]);
+        }
+        args.extend(packages.iter().map(String::as_str));
+        self.run_in_env(env_path, &args)
+    }
+
+    fn freeze(&self, env_path: &Path) -> Result<String> {
+        self.output_in_env(env_path, &["pip", "freeze"])
+    }
+
+    fn list_outdated(
+        &self,
+        env_path: &Path,
+        index_args: &[String],
+    ) -> Result<Vec<OutdatedPackage>> {
+        let mut args = vec!["pip", "list", "--outdated", "--format", "json"];
+        args.extend(index_args.iter().map(String::as_str));
+        let output = self.output_in_env(env_path, &args)?;
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&output).map_err(|e| {
+            MuvError::UvCommandFailed(format!("Failed to parse 'uv pip list' output: {}", e))
+        })
+    }
+
+    fn compile(&self, input: &Path, output: &Path, index_args: &[String]) -> Result<()> {
+        let input = input.to_string_lossy();
+        let output = output.to_string_lossy();
+        let mut args = vec!["pip", "compile", input.as_ref(), "-o", output.as_ref()];
+        args.extend(index_args.iter().map(String::as_str));
+        // Compiling only resolves, so any uv failure here is a resolution failure.
+        self.run(&args).map_err(|e| match e {
+            MuvError::UvCommandFailed(msg) => MuvError::ResolutionFailed(msg),
+            other => other,
+        })
+    }
+
+    fn sync(&self, env_path: &Path, requirements: &Path) -> Result<()> {
+        let requirements_str = requirements.to_string_lossy();
+        let mut args = vec!["pip", "sync", requirements_str.as_ref()];
+        // uv refuses to sync to an empty set unless told that is intended.
+        let is_empty = fs::read_to_string(requirements)?
+            .lines()
+            .map(str::trim)
+            .all(|line| line.is_empty() || line.starts_with('#'));
+        if is_empty {
+            args.push("--allow-empty-requirements");
+        }
+        self.run_in_env(env_path, &args)
+    }
+}
+
+/// Fallback backend using `python -m venv` and the environment's own pip. Slower than
+/// uv and without its extras (overrides, compiling), but needs nothing beyond Python.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PipBackend;
+
+impl PipBackend {
+    fn env_python(env_path: &Path) -> PathBuf {
+        if cfg!(windows) {
+            env_path.join("Scripts").join("python.exe")
+        } else {
+            env_path.join("bin").join("python")
+        }
+    }
+
+    /// The interpreter to create environments with: a bare version like `3.11` means
+    /// `python3.11`; anything else is used as an interpreter name or path.
+    fn base_python(python: Option<&str>) -> Result<String> {
+        match python {
+            None => Ok("python3".to_string()),
+            Some(version) if version.chars().all(|c| c.is_ascii_digit() || c == '.') => {
+                Ok(format!("python{}", version))
+            }
+            Some(spec) if spec.starts_with(['<', '>', '=', '!', '~']) => {
+                Err(MuvError::Anyhow(anyhow::anyhow!(
+                    "The pip backend cannot pick an interpreter for '{}'; pass a version or interpreter path instead.",
+                    spec
+                )))
+            }
+            Some(interpreter) => Ok(interpreter.to_string()),
+        }
+    }
+
+    fn pip(&self, env_path: &Path, args: &[&str]) -> Result<()> {
+        let mut pip_args = vec!["-m", "pip"];
+        pip_args.extend(args);
+        utils::run_command(&Self::env_python(env_path), &pip_args, vec![])
+    }
+
+    fn pip_output(&self, env_path: &Path, args: &[&str]) -> Result<String> {
+        let mut pip_args = vec!["-m", "pip"];
+        pip_args.extend(args);
+        utils::get_command_output(
+            &Self::env_python(env_path).to_string_lossy(),
+            &pip_args,
+            None,
+            vec![],
+        )
+    }
+
+    fn unsupported(&self, what: &str) -> MuvError {
+        MuvError::Anyhow(anyhow::anyhow!(
+            "{} is not supported by the pip backend; use the uv backend.",
+            what
+        ))
+    }
+}
+
+impl EnvBackend for PipBackend {
+    fn name(&self) -> &str {
+        "pip"
+    }
+
+    fn create_venv(&self, env_path: &Path, python: Option<&str>) -> Result<()> {
+        let python = Self::base_python(python)?;
+        if utils::find_executable(&python).is_none() {
+            return Err(MuvError::InterpreterNotFound(python));
+        }
+        let env_str = env_path.to_string_lossy();
+        utils::run_command(
+            Path::new(&python),
+            &["-m", "venv", env_str.as_ref()],
+            vec![],
+        )
+    }
+
+    fn install(&self, env_path: &Path, request: &InstallRequest) -> Result<()> {
+        if !request.overrides.is_empty() {
+            return Err(self.unsupported("--override"));
+        }
+        let mut args = vec!["install"];
+        if request.upgrade {
+            args.push("--upgrade");
+        }
+        for file in &request.requirements {
+            args.extend(["-r", file.as_str()]);
+        }
+        for path in &request.editables {
+            args.extend(["-e", path.as_str()]);
+        }
+        args.extend(request.packages.iter().map(String::as_str));
+        for file in &request.constraints {
+            args.extend(["-c", file.as_str()]);
+        }
+        if request.no_deps {
+            args.push("--no-deps");
+        }
+        args.extend(request.index_args.iter().map(String::as_str));
+        self.pip(env_path, &args)
+    }
+
+    fn uninstall(
+        &self,
+        env_path: &Path,
+        packages: &[String],
+        requirements: &[String],
+    ) -> Result<()> {
+        let mut args = vec!["uninstall", "--yes"];
+        for file in requirements {
+            args.extend(["-r", file.as_str()]);
+        }
+        args.extend(packages.iter().map(String::as_str));
+        self.pip(env_path, &args)
+    }
+
+    fn freeze(&self, env_path: &Path) -> Result<String> {
+        self.pip_output(env_path, &["freeze"])
+    }
+
+    fn list_outdated(
+        &self,
+        env_path: &Path,
+        index_args: &[String],
+    ) -> Result<Vec<OutdatedPackage>> {
+        let mut args = vec!["list", "--outdated", "--format", "json"];
+        args.extend(index_args.iter().map(String::as_str));
+        let output = self.pip_output(env_path, &args)?;
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&output).map_err(|e| {
+            MuvError::CommandFailed(format!("Failed to parse 'pip list' output: {}", e))
+        })
+    }
+
+    fn compile(&self, _input: &Path, _output: &Path, _index_args: &[String]) -> Result<()> {
+        Err(self.unsupported("Compiling requirements"))
+    }
+
+    /// pip has no sync: uninstall what the file doesn't list, then install the file.
+    fn sync(&self, env_path: &Path, requirements_file: &Path) -> Result<()> {
+        let wanted: Vec<String> =
+            requirements::parse_requirements(&fs::read_to_string(requirements_file)?)
+                .iter()
+                .map(|r| r.normalized_name())
+                .collect();
+        let extra: Vec<String> = requirements::parse_requirements(&self.freeze(env_path)?)
+            .into_iter()
+            .filter(|r| !wanted.contains(&r.normalized_name()))
+            .map(|r| r.name)
+            .collect();
+        if !extra.is_empty() {
+            self.uninstall(env_path, &extra, &[])?;
+        }
+        if !wanted.is_empty() {
+            let file = requirements_file.to_string_lossy();
+            self.pip(env_path, &["install", "-r", file.as_ref()])?;
+        }
+        Ok(())
+    }
+}
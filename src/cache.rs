@@ -0,0 +1,58 @@
+use crate::error::{MuvError, Result};
+use crate::staging::Staging;
+use crate::utils;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Written into a cached environment once it is fully built.
+const COMPLETE_MARKER: &str = ".muv-cache-complete";
+
+/// Directory under the muv home holding one kind of cached environment.
+pub fn cache_dir(kind: &str) -> Result<PathBuf> {
+    let path = utils::get_muv_home()?.join(kind);
+    fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// Short hex digest of `parts`, used to name a cached environment.
+pub fn cache_key<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            hasher.update(b"\n");
+        }
+        hasher.update(part.as_bytes());
+    }
+    let digest = hasher.finalize();
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Return the cached environment `key` in `dir`, calling `build` to create it if
+/// needed. It is built in a staging directory and renamed into place once complete,
+/// so concurrent runs never see or remove each other's half-built environments; when
+/// two build the same one, the first to finish is kept.
+pub fn ensure_cached(
+    dir: &Path,
+    key: &str,
+    build: impl FnOnce(&Path) -> Result<()>,
+) -> Result<PathBuf> {
+    let path = dir.join(key);
+    if path.join(COMPLETE_MARKER).exists() {
+        return Ok(path);
+    }
+    // Left partial by a build that happened in place, before staging was used.
+    match fs::remove_dir_all(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+
+    let staging = Staging::new(dir, key, false)?;
+    build(staging.path())?;
+    fs::write(staging.path().join(COMPLETE_MARKER), "")?;
+    match staging.commit(&path) {
+        Err(MuvError::EnvironmentAlreadyExists(_)) => Ok(path),
+        result => result.map(|()| path),
+    }
+}
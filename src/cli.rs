@@ -1,4 +1,8 @@
+use crate::backend::BackendKind;
+use crate::config::Config;
+use crate::hooks::HookKind;
 use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -13,6 +17,37 @@ use clap::{Args, Parser, Subcommand};
 pub struct Cli {
     #[clap(subcommand)]
     pub command: Commands,
+
+    /// Tool used to create environments and manage packages
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        env = "MUV_BACKEND",
+        help = "Backend to use (uv or pip)",
+        long_help = "The tool used to create environments and manage their packages. 'uv' (the default) is fastest; 'pip' uses 'python -m venv' and the environment's own pip, for hosts where uv can't be installed."
+    )]
+    pub backend: Option<BackendKind>,
+
+    /// Machine-readable output
+    #[clap(
+        long,
+        global = true,
+        help = "Output as JSON",
+        long_help = "Print machine-readable JSON. Errors are printed to stderr as {\"error\": {\"kind\", \"code\", \"message\", \"hint\"}}; commands that support it (such as diff) print their results as JSON too."
+    )]
+    pub json: bool,
+
+    /// Fail instead of waiting for an environment another muv command is using
+    #[clap(
+        long,
+        global = true,
+        env = "MUV_NO_WAIT",
+        value_parser = clap::builder::BoolishValueParser::new(),
+        help = "Fail instead of waiting for a busy environment",
+        long_help = "muv locks an environment while it uses it: 'run' and 'freeze' share it, while 'install', 'uninstall', 'upgrade', 'rollback' and 'delete' need it to themselves. By default a command waits until a conflicting one has finished; with --no-wait it fails with 'env_busy' (exit code 14) instead."
+    )]
+    pub no_wait: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -51,12 +86,26 @@ pub enum Commands {
     Deactivate,
 
     /// Delete a virtual environment
-    #[clap(long_about = "Permanently delete a virtual environment and all its installed packages")]
+    #[clap(
+        long_about = "Delete a virtual environment by moving it to the trash (<muv home>/trash), from where 'muv restore' can bring it back. With --permanent it is removed right away."
+    )]
     Delete(DeleteArgs),
 
+    /// Bring back a deleted environment
+    #[clap(
+        long_about = "Move the most recently deleted environment with this name out of the trash and back to where it was deleted from"
+    )]
+    Restore(RestoreArgs),
+
+    /// List or empty the trash of deleted environments
+    #[clap(
+        long_about = "Show the environments in the trash, or delete them for good, optionally only those deleted a while ago"
+    )]
+    Trash(TrashArgs),
+
     /// Install packages in an environment
     #[clap(long_about = "Install Python packages in the specified or active environment using uv")]
-    Install(PackageManagementArgs),
+    Install(InstallArgs),
 
     /// Uninstall packages from an environment
     #[clap(long_about = "Uninstall Python packages from the specified or active environment")]
@@ -68,6 +117,94 @@ pub enum Commands {
     )]
     Freeze(EnvNameArg),
 
+    /// List packages with newer versions available
+    #[clap(
+        long_about = "List installed packages in the specified or active environment that have newer versions available from the configured index"
+    )]
+    Outdated(OutdatedArgs),
+
+    /// Upgrade packages in an environment
+    #[clap(
+        long_about = "Upgrade packages in the specified or active environment using 'uv pip install --upgrade' and report what changed"
+    )]
+    Upgrade(UpgradeArgs),
+
+    /// Show the history of changes to an environment
+    #[clap(
+        long_about = "Show the operations (create, install, uninstall, upgrade, rollback) applied to the specified or active environment"
+    )]
+    History(EnvNameArg),
+
+    /// Restore an earlier package set of an environment
+    #[clap(
+        long_about = "Restore the packages of the specified or active environment to a previous point in its history using 'uv pip sync'"
+    )]
+    Rollback(RollbackArgs),
+
+    /// Compare the packages of two environments
+    #[clap(
+        long_about = "Show packages added, removed or changed between two environments, or between an environment and a requirements file"
+    )]
+    Diff(DiffArgs),
+
+    /// Show details about an environment
+    #[clap(
+        long_about = "Show the name, path and Python version of the specified environment, and how it was selected"
+    )]
+    Info(EnvNameArg),
+
+    /// Bind the current directory to an environment
+    #[clap(
+        long_about = "Write a .muv-env file in the current directory so that commands run here (or in subdirectories) use the given environment when no name is specified and none is active"
+    )]
+    Use(UseArgs),
+
+    /// Tag an environment, or show its tags
+    #[clap(
+        long_about = "Attach tags to an environment so groups of environments can be selected together, e.g. with 'muv run --tag'. Without tags, prints the environment's current tags."
+    )]
+    Tag(TagArgs),
+
+    /// Manage environment variables of an environment
+    #[clap(
+        long_about = "Set, unset or list variables that are exported when the environment is activated and when commands run in it with 'muv run'. Variables can also be put in a .env file at the root of the environment; those set with 'muv env set' take precedence."
+    )]
+    Env(EnvVarsArgs),
+
+    /// Manage activation hooks of an environment
+    #[clap(
+        long_about = "Manage shell scripts stored in an environment that are sourced right after it is activated (post-activate) or right before it is deactivated (pre-deactivate)"
+    )]
+    Hooks(HooksArgs),
+
+    /// Make commands of an environment available on PATH
+    #[clap(
+        long_about = "Create shims in the muv bin directory (added to PATH by 'muv init') that run commands from an environment without activating it, like pipx apps"
+    )]
+    Expose(ExposeArgs),
+
+    /// Remove exposed commands
+    #[clap(long_about = "Remove the shims of commands previously exposed with 'muv expose'")]
+    Unexpose(UnexposeArgs),
+
+    /// List exposed commands
+    #[clap(
+        long_about = "List the commands exposed with 'muv expose' and the environment each runs from"
+    )]
+    Exposed,
+
+    /// Find the environments that provide a command
+    #[clap(
+        long_about = "Search every environment's bin directory for a command and report which environments provide it, the package that installed it, and which one runs given the current activation"
+    )]
+    Which(WhichArgs),
+
+    /// View or change the global configuration
+    #[clap(
+        long_about = "View or change muv's configuration file (~/.config/muv/config.toml, or MUV_CONFIG). Command-line flags override environment variables, which override the file, which overrides the built-in defaults."
+    )]
+    Config(ConfigArgs),
+
     /// Print the path to an environment
     #[clap(long_about = "Display the full filesystem path to the specified environment")]
     Path(EnvNameArg),
@@ -99,6 +236,23 @@ pub struct CreateArgs {
     )]
     pub python: Option<String>,
 
+    /// Root to create the environment in (see MUV_ENVS_PATH)
+    #[clap(
+        long,
+        value_name = "ROOT",
+        help = "Root to create the environment in",
+        long_help = "Name of the root (from MUV_ENVS_PATH) to create the environment in. Defaults to the first root."
+    )]
+    pub root: Option<String>,
+
+    /// Keep the partly built environment if creating it fails
+    #[clap(
+        long,
+        help = "Keep the partly built environment on failure",
+        long_help = "Environments are built in a staging directory and only moved into place once everything succeeded; a failed or interrupted create removes it again. With this flag it is kept (and its location printed) so you can look into what went wrong."
+    )]
+    pub keep_on_failure: bool,
+
     #[clap(required = true, num_args = 1.., help = "Packages to install", long_help = "List of packages to install or uninstall. You can specify version constraints using standard pip syntax (e.g., 'flask>=2.0', 'requests==2.28.1').")]
     pub packages: Option<Vec<String>>,
 }
@@ -120,30 +274,327 @@ pub struct EnvNameArg {
     #[clap(
         value_name = "ENV_NAME",
         help = "Environment name",
-        long_help = "Specify the name of the environment to use. If not provided and an environment is currently active, the active environment will be used."
+        long_help = "Specify the name of the environment to use. If not provided, the active environment is used, or else the one named in the nearest .muv-env file."
     )]
     pub name: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct UseArgs {
+    /// Name of the environment to bind to the current directory
+    #[clap(
+        value_name = "ENV_NAME",
+        help = "Environment name",
+        long_help = "Specify the environment that commands run in this directory should use by default."
+    )]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct TagArgs {
+    /// Name of the environment to tag
+    #[clap(
+        value_name = "ENV_NAME",
+        help = "Environment name",
+        long_help = "Specify the name of the environment whose tags to change or show."
+    )]
+    pub name: String,
+
+    /// Tags to add (or remove with --remove)
+    #[clap(
+        value_name = "TAG",
+        help = "Tags to add",
+        long_help = "Tags to attach to the environment. Environments sharing a tag can be selected together with 'muv run --tag'."
+    )]
+    pub tags: Vec<String>,
+
+    /// Remove the given tags instead of adding them
+    #[clap(
+        long,
+        requires = "tags",
+        help = "Remove the tags",
+        long_help = "Remove the given tags from the environment instead of adding them."
+    )]
+    pub remove: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct EnvVarsArgs {
+    #[clap(subcommand)]
+    pub command: EnvVarsCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnvVarsCommand {
+    /// Set variables for an environment
+    #[clap(long_about = "Store variables that are exported whenever the environment is used")]
+    Set {
+        #[clap(
+            value_name = "ENV_NAME",
+            help = "Environment name",
+            long_help = "Specify the name of the environment to set variables for."
+        )]
+        name: String,
+
+        #[clap(
+            value_name = "KEY=VALUE",
+            required = true,
+            num_args = 1..,
+            value_parser = parse_key_value,
+            help = "Variables to set",
+            long_help = "Variables to set, e.g. CUDA_VISIBLE_DEVICES=0. Existing values are replaced."
+        )]
+        vars: Vec<(String, String)>,
+    },
+
+    /// Remove variables from an environment
+    #[clap(long_about = "Remove variables previously stored with 'muv env set'")]
+    Unset {
+        #[clap(
+            value_name = "ENV_NAME",
+            help = "Environment name",
+            long_help = "Specify the name of the environment to remove variables from."
+        )]
+        name: String,
+
+        #[clap(
+            value_name = "KEY",
+            required = true,
+            num_args = 1..,
+            help = "Variables to remove",
+            long_help = "Names of the variables to remove."
+        )]
+        keys: Vec<String>,
+    },
+
+    /// List the variables of an environment
+    #[clap(
+        long_about = "List the variables applied for the environment, from both 'muv env set' and its .env file"
+    )]
+    List(EnvNameArg),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[clap(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print a setting
+    #[clap(long_about = "Print the value of a setting in the config file")]
+    Get {
+        #[clap(
+            value_name = "KEY",
+            help = "Setting to print",
+            long_help = "One of: python, default-packages, index-url, extra-index-url, envs-dir, prompt, output, confirm-delete, backend."
+        )]
+        key: String,
+    },
+
+    /// Change a setting
+    #[clap(long_about = "Change a setting in the config file. List values are comma-separated.")]
+    Set {
+        #[clap(
+            value_name = "KEY",
+            help = "Setting to change",
+            long_help = "One of: python, default-packages, index-url, extra-index-url, envs-dir, prompt, output, confirm-delete, backend."
+        )]
+        key: String,
+
+        #[clap(
+            value_name = "VALUE",
+            allow_hyphen_values = true,
+            help = "New value",
+            long_help = "The new value. Lists (default-packages, extra-index-url) are comma-separated, e.g. 'ipython,rich'."
+        )]
+        value: String,
+    },
+
+    /// Reset a setting to its default
+    #[clap(long_about = "Remove a setting from the config file, so its default applies again")]
+    Unset {
+        #[clap(value_name = "KEY", help = "Setting to reset")]
+        key: String,
+    },
+
+    /// List all settings
+    #[clap(long_about = "List every setting and its value in the config file")]
+    List,
+
+    /// Print the path of the config file
+    #[clap(long_about = "Print the path of the config file, whether or not it exists yet")]
+    Path,
+}
+
+#[derive(Args, Debug)]
+pub struct HooksArgs {
+    #[clap(subcommand)]
+    pub command: HooksCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksCommand {
+    /// Edit a hook script
+    #[clap(
+        long_about = "Open a hook script of the environment in $VISUAL or $EDITOR, creating it if needed"
+    )]
+    Edit {
+        #[clap(
+            value_name = "ENV_NAME",
+            help = "Environment name",
+            long_help = "Specify the name of the environment whose hook to edit."
+        )]
+        name: String,
+
+        #[clap(
+            value_enum,
+            help = "Hook to edit",
+            long_help = "The hook to edit: post-activate runs after activation, pre-deactivate before deactivation."
+        )]
+        hook: HookKind,
+    },
+
+    /// Delete a hook script
+    #[clap(long_about = "Delete a hook script of the environment")]
+    Remove {
+        #[clap(
+            value_name = "ENV_NAME",
+            help = "Environment name",
+            long_help = "Specify the name of the environment whose hook to delete."
+        )]
+        name: String,
+
+        #[clap(value_enum, help = "Hook to delete", long_help = "The hook to delete.")]
+        hook: HookKind,
+    },
+
+    /// List the hooks of an environment
+    #[clap(long_about = "Show which hook scripts the environment has and where they are")]
+    List(EnvNameArg),
+}
+
+#[derive(Args, Debug)]
+pub struct ExposeArgs {
+    /// Name of the environment providing the commands
+    #[clap(
+        value_name = "ENV_NAME",
+        help = "Environment name",
+        long_help = "Specify the name of the environment whose commands to expose."
+    )]
+    pub name: String,
+
+    /// Commands to expose (e.g., black, http)
+    #[clap(
+        value_name = "COMMAND",
+        required = true,
+        num_args = 1..,
+        help = "Commands to expose",
+        long_help = "Names of executables in the environment's bin directory to make available on PATH."
+    )]
+    pub commands: Vec<String>,
+
+    /// Replace commands already exposed from another environment
+    #[clap(
+        long,
+        help = "Replace existing commands",
+        long_help = "Replace a command that is already exposed from another environment, or an existing file of the same name in the muv bin directory."
+    )]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct UnexposeArgs {
+    /// Commands to remove
+    #[clap(
+        value_name = "COMMAND",
+        required = true,
+        num_args = 1..,
+        help = "Commands to remove",
+        long_help = "Names of previously exposed commands whose shims should be removed."
+    )]
+    pub commands: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct WhichArgs {
+    /// Command to look for (e.g., jupyter-lab)
+    #[clap(
+        value_name = "COMMAND",
+        help = "Command to look for",
+        long_help = "Name of the executable to look for in each environment's bin directory."
+    )]
+    pub command: String,
+}
+
 #[derive(Args, Debug)]
 pub struct DeleteArgs {
     /// Name of the environment to delete
     #[clap(
         help = "Environment to delete",
-        long_help = "Specify the name of the environment you want to permanently delete."
+        long_help = "Specify the name of the environment you want to delete."
     )]
     pub name: String,
 
+    /// Delete right away instead of moving to the trash
+    #[clap(
+        long,
+        help = "Delete permanently instead of moving to the trash",
+        long_help = "Remove the environment immediately instead of moving it to the trash. It can't be restored afterwards."
+    )]
+    pub permanent: bool,
+
     /// Skip confirmation prompt
     #[clap(
         short,
         long,
         help = "Skip confirmation",
-        long_help = "Skip the confirmation prompt and immediately delete the environment. Use with caution as this action cannot be undone."
+        long_help = "Skip the confirmation prompt and immediately delete the environment. Use with caution together with --permanent, as that cannot be undone."
     )]
     pub yes: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    /// Name of the environment to restore
+    #[clap(
+        help = "Environment to restore",
+        long_help = "Name of the deleted environment. If it was deleted several times, the most recent copy is restored."
+    )]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct TrashArgs {
+    #[clap(subcommand)]
+    pub command: TrashCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCommand {
+    /// List deleted environments
+    #[clap(
+        long_about = "List the environments in the trash, with when they were deleted and where from"
+    )]
+    List,
+
+    /// Delete environments in the trash for good
+    #[clap(
+        long_about = "Permanently remove the environments in the trash, or only those deleted longer ago than --older-than"
+    )]
+    Empty {
+        #[clap(
+            long,
+            value_name = "DURATION",
+            value_parser = humantime::parse_duration,
+            help = "Only remove environments deleted longer ago than this",
+            long_help = "Only remove environments deleted longer ago than this, e.g. '30days', '2weeks' or '12h'."
+        )]
+        older_than: Option<std::time::Duration>,
+    },
+}
+
 #[derive(Args, Debug)]
 pub struct PackageManagementArgs {
     /// Name of the environment (optional if an environment is active)
@@ -152,7 +603,7 @@ pub struct PackageManagementArgs {
         long,
         value_name = "ENV_NAME",
         help = "Environment name",
-        long_help = "Specify the name of the environment to manage packages in. If not provided and an environment is currently active, the active environment will be used."
+        long_help = "Specify the name of the environment to manage packages in. If not provided, the active environment is used, or else the one named in the nearest .muv-env file. Unlike pip and uv, -e is not --editable: use --editable for editable installs."
     )]
     pub env_name: Option<String>,
 
@@ -160,15 +611,21 @@ pub struct PackageManagementArgs {
     #[clap(required = false, num_args = 0.., help = "Packages to manage", long_help = "List of packages to install or uninstall. You can specify version constraints using standard pip syntax (e.g., 'flask>=2.0', 'requests==2.28.1').")]
     pub packages: Vec<String>,
 
-    /// Install from requirements file
+    /// Requirements files to install from or uninstall
     #[clap(
         short,
         long,
         value_name = "REQUIREMENTS",
-        help = "Install from requirements.txt",
-        long_help = "Install all dependencies listed in the given requirements.txt file."
+        help = "Use a requirements.txt",
+        long_help = "Install (or uninstall) all dependencies listed in the given requirements.txt file. Can be given multiple times."
     )]
-    pub requirements: Option<String>,
+    pub requirements: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    #[clap(flatten)]
+    pub packages: PackageManagementArgs,
 
     #[clap(
         short = 't',
@@ -177,18 +634,346 @@ pub struct PackageManagementArgs {
         long_help = "Install all dependencies listed in the given pyproject.toml file"
     )]
     pub toml: Option<String>,
+
+    /// Optional-dependency extras to install from pyproject.toml
+    #[clap(
+        long = "extra",
+        value_name = "EXTRA",
+        requires = "toml",
+        help = "Install an extra from pyproject.toml",
+        long_help = "Also install the dependencies of the given [project.optional-dependencies] extra. Can be given multiple times."
+    )]
+    pub extras: Vec<String>,
+
+    /// Install every optional-dependency extra from pyproject.toml
+    #[clap(
+        long,
+        requires = "toml",
+        conflicts_with = "extras",
+        help = "Install all extras from pyproject.toml",
+        long_help = "Also install the dependencies of every [project.optional-dependencies] extra."
+    )]
+    pub all_extras: bool,
+
+    /// Dependency groups (PEP 735) to install from pyproject.toml
+    #[clap(
+        long = "group",
+        value_name = "GROUP",
+        requires = "toml",
+        help = "Install a dependency group from pyproject.toml",
+        long_help = "Also install the requirements of the given [dependency-groups] group, including any groups it includes. Can be given multiple times."
+    )]
+    pub groups: Vec<String>,
+
+    /// Install the project itself, not just its dependencies
+    #[clap(
+        long,
+        requires = "toml",
+        help = "Install the project itself",
+        long_help = "Install the project defined by the pyproject.toml in addition to its dependencies. To install it in editable mode, pass its directory to --editable instead."
+    )]
+    pub project: bool,
+
+    /// Local projects to install in editable mode
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Install a local project as editable (no -e short form)",
+        long_help = "Install the project at the given path in editable mode, so changes to its source take effect without reinstalling. Can be given multiple times. There is no short form: unlike pip and uv, -e is the environment name (--env-name), so 'muv install -e ./path' looks for an environment called './path'."
+    )]
+    pub editable: Vec<String>,
+
+    /// Constraints files
+    #[clap(
+        short = 'c',
+        long = "constraint",
+        value_name = "CONSTRAINTS",
+        help = "Constrain versions using a file",
+        long_help = "Constrain the versions of resolved packages using the given constraints file, without installing anything from it. Can be given multiple times."
+    )]
+    pub constraints: Vec<String>,
+
+    /// Overrides files
+    #[clap(
+        long = "override",
+        value_name = "OVERRIDES",
+        help = "Override versions using a file",
+        long_help = "Override the versions of resolved packages using the given file, even if they conflict with other requirements. Can be given multiple times."
+    )]
+    pub overrides: Vec<String>,
+
+    /// Don't install package dependencies
+    #[clap(
+        long,
+        help = "Don't install dependencies",
+        long_help = "Install only the requested packages, ignoring their dependencies."
+    )]
+    pub no_deps: bool,
+
+    #[clap(flatten)]
+    pub index: IndexArgs,
 }
 
 #[derive(Args, Debug)]
-pub struct RunArgs {
-    /// Name of the environment
+pub struct IndexArgs {
+    /// Base URL of the package index
+    #[clap(
+        long,
+        value_name = "URL",
+        help = "Package index URL",
+        long_help = "The URL of the Python package index to use instead of the default (PyPI)."
+    )]
+    pub index_url: Option<String>,
+
+    /// Additional package index URLs
+    #[clap(
+        long,
+        value_name = "URL",
+        help = "Extra package index URL",
+        long_help = "Additional package index URLs to use alongside the main index. Can be given multiple times."
+    )]
+    pub extra_index_url: Vec<String>,
+
+    /// Local directories or URLs to search for distributions
+    #[clap(
+        short = 'f',
+        long,
+        value_name = "PATH_OR_URL",
+        help = "Find links location",
+        long_help = "Local directories or HTML pages to search for distributions, in addition to the index. Can be given multiple times."
+    )]
+    pub find_links: Vec<String>,
+}
+
+impl IndexArgs {
+    /// The equivalent `uv pip` arguments. Index URLs not given on the command line
+    /// come from the config file.
+    pub fn to_uv_args(&self, config: &Config) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(url) = self.index_url.as_ref().or(config.index_url.as_ref()) {
+            args.push("--index-url".to_string());
+            args.push(url.clone());
+        }
+        let extra_index_urls = if self.extra_index_url.is_empty() {
+            &config.extra_index_url
+        } else {
+            &self.extra_index_url
+        };
+        for url in extra_index_urls {
+            args.push("--extra-index-url".to_string());
+            args.push(url.clone());
+        }
+        for location in &self.find_links {
+            args.push("--find-links".to_string());
+            args.push(location.clone());
+        }
+        args
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct OutdatedArgs {
+    #[clap(flatten)]
+    pub env: EnvNameArg,
+
+    #[clap(flatten)]
+    pub index: IndexArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct UpgradeArgs {
+    /// Name of the environment (optional with --all if an environment is active)
+    #[clap(
+        value_name = "ENV_NAME",
+        help = "Environment name",
+        long_help = "Specify the name of the environment to upgrade packages in. It must be given when packages are listed; with --all it may be left out, and the active environment is used, or else the one named in the nearest .muv-env file."
+    )]
+    pub name: Option<String>,
+
+    /// Packages to upgrade
     #[clap(
+        num_args = 0..,
+        required_unless_present = "all",
+        conflicts_with = "all",
+        help = "Packages to upgrade",
+        long_help = "List of packages to upgrade. Version constraints are allowed (e.g., 'flask<3')."
+    )]
+    pub packages: Vec<String>,
+
+    /// Upgrade every installed package
+    #[clap(
+        long,
+        help = "Upgrade all packages",
+        long_help = "Upgrade every package installed in the environment."
+    )]
+    pub all: bool,
+
+    /// Show what would be upgraded without changing anything
+    #[clap(
+        long,
+        help = "Only show what would change",
+        long_help = "List the upgrades that would be applied without modifying the environment."
+    )]
+    pub dry_run: bool,
+
+    #[clap(flatten)]
+    pub index: IndexArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct RollbackArgs {
+    /// Name of the environment (optional if an environment is active)
+    #[clap(
+        value_name = "ENV_NAME",
         help = "Environment name",
-        long_help = "Specify the name of the environment in which to run the command."
+        long_help = "Specify the name of the environment to roll back. If not provided, the active environment is used, or else the one named in the nearest .muv-env file."
+    )]
+    pub name: Option<String>,
+
+    /// History entry to restore
+    #[clap(
+        long,
+        value_name = "N",
+        help = "History entry to restore",
+        long_help = "Restore the packages as they were right after history entry N (see 'muv history'). Defaults to undoing the most recent operation."
+    )]
+    pub to: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Environment to compare from
+    #[clap(
+        value_name = "ENV_NAME",
+        help = "Environment to compare from",
+        long_help = "Specify the environment whose installed packages form the base of the comparison."
     )]
     pub env_name: String,
 
+    /// Environment to compare against
+    #[clap(
+        value_name = "OTHER_ENV",
+        required_unless_present = "requirements",
+        conflicts_with = "requirements",
+        help = "Environment to compare against",
+        long_help = "Specify a second environment. Packages only in this environment are reported as added, packages only in the first as removed."
+    )]
+    pub other_env: Option<String>,
+
+    /// Compare against a requirements file instead of another environment
+    #[clap(
+        short,
+        long,
+        value_name = "REQUIREMENTS",
+        help = "Compare against a requirements.txt",
+        long_help = "Compare the environment against the packages listed in the given requirements.txt file instead of another environment."
+    )]
+    pub requirements: Option<String>,
+
+    /// Print the differences as a JSON object with 'added', 'removed' and 'changed'
+    /// lists; set from the global `--json` flag.
+    #[clap(skip)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Name of the environment (optional if an environment is active or bound with `muv use`)
+    #[clap(
+        conflicts_with_all = ["all", "tags"],
+        help = "Environment name",
+        long_help = "Specify the name of the environment in which to run the command. If not provided, the active environment is used, or else the one named in the nearest .muv-env file."
+    )]
+    pub env_name: Option<String>,
+
+    /// Directory to run the command in
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Working directory for the command",
+        long_help = "Run the command in the given directory instead of the current one."
+    )]
+    pub cwd: Option<PathBuf>,
+
+    /// Extra environment variables for the command
+    #[clap(
+        long = "env",
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value,
+        help = "Set an environment variable",
+        long_help = "Set an environment variable for the command, e.g. --env DEBUG=1. Can be given multiple times."
+    )]
+    pub env: Vec<(String, String)>,
+
+    /// Extra packages available only for this command
+    #[clap(
+        long = "with",
+        value_name = "PACKAGE",
+        help = "Run with an extra package",
+        long_help = "Make an extra package available for this command only, without installing it into the environment. The package goes into a cached overlay layered on top of the environment. Can be given multiple times."
+    )]
+    pub with: Vec<String>,
+
+    /// Run the command in every environment
+    #[clap(
+        long,
+        conflicts_with = "tags",
+        help = "Run in every environment",
+        long_help = "Run the command once in each environment managed by muv and print a summary of the results."
+    )]
+    pub all: bool,
+
+    /// Run the command in every environment with this tag
+    #[clap(
+        long = "tag",
+        value_name = "TAG",
+        help = "Run in environments with a tag",
+        long_help = "Run the command once in each environment carrying the given tag (see 'muv tag') and print a summary of the results. Can be given multiple times to select environments with any of the tags."
+    )]
+    pub tags: Vec<String>,
+
+    /// Number of environments to run in at once with --all or --tag
+    #[clap(
+        short = 'j',
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Parallel jobs",
+        long_help = "With --all or --tag, run the command in up to N environments at the same time. Output lines are prefixed with the environment name."
+    )]
+    pub jobs: u32,
+
+    /// Run a Python script with inline (PEP 723) metadata, followed by its arguments
+    #[clap(
+        long,
+        value_name = "SCRIPT",
+        num_args = 1..,
+        allow_hyphen_values = true,
+        conflicts_with_all = ["env_name", "all", "tags", "command_and_args"],
+        help = "Run a script with inline metadata",
+        long_help = "Run a Python script in a cached environment built from its inline '# /// script' metadata (PEP 723): the Python version from 'requires-python' and the packages from 'dependencies'. Everything after the script path is passed to the script, so it can be used in a shebang: '#!/usr/bin/env -S muv run --script'."
+    )]
+    pub script: Vec<String>,
+
+    /// Replace the muv process with the command (Unix only)
+    #[clap(
+        long,
+        conflicts_with_all = ["all", "tags"],
+        help = "Exec the command in place of muv",
+        long_help = "Replace the muv process with the command instead of running it as a child, so the process tree stays clean and signals go straight to the command. Unix only."
+    )]
+    pub exec: bool,
+
     /// The command and its arguments to run (e.g., python script.py --arg value)
-    #[clap(last = true, required = true, num_args = 1.., help = "Command to run", long_help = "The command and its arguments to run within the specified environment. For example: 'python script.py --arg value'")]
+    #[clap(last = true, required_unless_present = "script", num_args = 1.., help = "Command to run", long_help = "The command and its arguments to run within the specified environment. For example: 'python script.py --arg value'")]
     pub command_and_args: Vec<String>,
 }
+
+/// Parse a `KEY=VALUE` pair.
+pub fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
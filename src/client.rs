@@ -0,0 +1,491 @@
+use crate::backend::{self, BackendKind, EnvBackend, InstallRequest};
+use crate::config::Config;
+use crate::error::{MuvError, Result};
+use crate::history::{self, HistoryEntry};
+use crate::lock::{self, EnvLock, LockMode};
+use crate::requirements::{self, OutdatedPackage, Requirement, RequirementsDiff};
+use crate::roots::{self, EnvRoot, RootedEnv};
+use crate::staging::Staging;
+use crate::trash::{self, TrashEntry};
+use crate::utils;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// An environment managed by muv.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvInfo {
+    pub name: String,
+    pub path: PathBuf,
+    /// The Python version from the environment's `pyvenv.cfg`, if recorded.
+    pub python_version: Option<String>,
+    pub tags: Vec<String>,
+    /// Name of the root the environment lives in.
+    pub root: String,
+    /// The root whose environment of the same name hides this one, if any.
+    pub shadowed_by: Option<String>,
+}
+
+/// An environment to create with [`Muv::create`].
+#[derive(Debug, Clone, Default)]
+pub struct EnvSpec {
+    pub name: String,
+    /// Python version or interpreter; the configured `python`, or the backend's
+    /// default, when `None`.
+    pub python: Option<String>,
+    /// Packages to install right after creating it, besides the configured
+    /// `default-packages`.
+    pub packages: Vec<String>,
+    /// Name of the root to create it in; the first root when `None`.
+    pub root: Option<String>,
+    /// Leave the partly built environment in the root's staging directory if
+    /// creating it fails, instead of removing it.
+    pub keep_on_failure: bool,
+}
+
+/// A muv client: the operations behind the CLI commands, returning data instead of
+/// printing it. Progress messages ("Creating environment ...") go to the output sink.
+///
+/// It covers managing environments and their packages. Commands that are about the
+/// user's shell or run other programs (`activate`, `run`, `init`, `expose`, `hooks`,
+/// `env`) stay CLI-only.
+pub struct Muv {
+    home: PathBuf,
+    config: Config,
+    roots: Option<Vec<EnvRoot>>,
+    wait: bool,
+    backend: Arc<dyn EnvBackend>,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Muv {
+    /// A client for the muv home at `home`. Pass `Box::new(std::io::sink())` as `output`
+    /// to discard progress messages.
+    pub fn new(
+        home: impl Into<PathBuf>,
+        backend: Arc<dyn EnvBackend>,
+        output: Box<dyn Write + Send>,
+    ) -> Self {
+        Self {
+            home: home.into(),
+            config: Config::default(),
+            roots: None,
+            wait: true,
+            backend,
+            output: Mutex::new(output),
+        }
+    }
+
+    /// The client the CLI uses: the muv home from `MUV_HOME` (or the default location),
+    /// the configuration from the config file and environment, and progress messages on
+    /// stdout. `backend` is the one chosen on the command line, if any; otherwise the
+    /// configured one is used.
+    pub fn from_env(backend: Option<BackendKind>) -> Result<Self> {
+        let config = Config::load()?;
+        let backend = backend.or(config.backend).unwrap_or_default();
+        Ok(Self::new(
+            utils::get_muv_home()?,
+            backend::backend_for(backend).into(),
+            Box::new(io::stdout()),
+        )
+        .with_config(config)
+        .with_roots(utils::get_env_roots()?))
+    }
+
+    /// Use `config` for defaults (Python, default packages, index URLs, envs directory).
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Search these roots for environments, in order, instead of the single envs
+    /// directory.
+    pub fn with_roots(mut self, roots: Vec<EnvRoot>) -> Self {
+        self.roots = Some(roots);
+        self
+    }
+
+    /// Whether to wait for environments other muv processes are using (the default),
+    /// or fail with [`MuvError::EnvironmentBusy`].
+    pub fn with_wait(mut self, wait: bool) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn backend(&self) -> &dyn EnvBackend {
+        self.backend.as_ref()
+    }
+
+    /// The directories environments live in, searched in order.
+    pub fn roots(&self) -> Vec<EnvRoot> {
+        self.roots.clone().unwrap_or_else(|| {
+            vec![EnvRoot {
+                name: roots::DEFAULT_ROOT.to_string(),
+                path: self
+                    .config
+                    .envs_dir()
+                    .unwrap_or_else(|| self.home.join("envs")),
+            }]
+        })
+    }
+
+    /// The first root, where new environments go by default.
+    pub fn envs_dir(&self) -> Result<PathBuf> {
+        let path = self.roots().swap_remove(0).path;
+        fs::create_dir_all(&path)?;
+        Ok(path)
+    }
+
+    /// The environment called `name` (from the first root that has one), or
+    /// [`MuvError::EnvironmentNotFound`].
+    pub fn env(&self, name: &str) -> Result<EnvInfo> {
+        utils::check_env_lookup_name(name)?;
+        let roots = self.roots();
+        let (path, root) = roots::find_env(&roots, name)
+            .ok_or_else(|| MuvError::EnvironmentNotFound(name.to_string()))?;
+        Ok(Self::env_info(RootedEnv {
+            name: name.to_string(),
+            path,
+            root: root.name.clone(),
+            shadowed_by: None,
+        }))
+    }
+
+    /// The environment a command should use: `name`, or else the active environment or
+    /// the one named in the nearest `.muv-env` file (see [`utils::resolve_env`]). Says
+    /// so on stderr when it wasn't named.
+    pub fn resolve_env(&self, name: Option<&String>) -> Result<(PathBuf, String)> {
+        Ok(utils::announce_resolved(utils::resolve_env_in(
+            &self.roots(),
+            name,
+        )?))
+    }
+
+    /// Lock the environment called `name` for as long as the returned lock lives (see
+    /// [`lock::lock_env`]). Fails with [`MuvError::EnvironmentNotFound`] if it doesn't
+    /// exist once the lock is held, e.g. because it was deleted while waiting.
+    pub fn lock(&self, name: &str, mode: LockMode) -> Result<EnvLock> {
+        utils::check_env_lookup_name(name)?;
+        let env_lock = lock::lock_env(&self.home, name, mode, self.wait)?;
+        self.env(name)?;
+        Ok(env_lock)
+    }
+
+    /// All environments in all roots, sorted by name. Environments hidden by one of
+    /// the same name in an earlier root are included, with `shadowed_by` set.
+    pub fn list_envs(&self) -> Result<Vec<EnvInfo>> {
+        Ok(roots::scan(&self.roots())?
+            .into_iter()
+            .map(Self::env_info)
+            .collect())
+    }
+
+    /// Create an environment and install its initial packages. It's built in a
+    /// staging directory and only moved into place once everything succeeded, so a
+    /// failed create leaves nothing behind. Signal handling is left to the caller (see
+    /// [`DeferredSignals`](crate::staging::DeferredSignals)).
+    pub fn create(&self, spec: &EnvSpec) -> Result<EnvInfo> {
+        utils::validate_env_name(&spec.name)?;
+        let roots = self.roots();
+        let root = match &spec.root {
+            Some(name) => roots::find_root(&roots, name)?,
+            None => &roots[0],
+        };
+        let env_path = root.path.join(&spec.name);
+        if env_path.exists() {
+            return Err(MuvError::EnvironmentAlreadyExists(spec.name.clone()));
+        }
+        let shadowing = roots::find_env(&roots, &spec.name).map(|(_, other)| other);
+        if let Some(other) = shadowing {
+            let position = |r: &EnvRoot| roots.iter().position(|x| x.name == r.name);
+            if position(other) < position(root) {
+                return Err(MuvError::Anyhow(anyhow::anyhow!(
+                    "Environment '{}' already exists in root '{}', which comes first in {}, so a new one in '{}' would be hidden by it.",
+                    spec.name,
+                    other.name,
+                    roots::ENVS_PATH_VAR,
+                    root.name
+                )));
+            }
+            self.say(format_args!(
+                "Note: the new environment hides '{}' in root '{}'.",
+                spec.name, other.name
+            ));
+        }
+
+        let python = spec.python.as_ref().or(self.config.python.as_ref());
+        let mut packages = spec.packages.clone();
+        for package in &self.config.default_packages {
+            if !packages.contains(package) {
+                packages.push(package.clone());
+            }
+        }
+
+        let staging = Staging::new(&root.path, &spec.name, spec.keep_on_failure)?;
+        let build_path = staging.path();
+        self.say(format_args!(
+            "Creating environment '{}' at {} (using {})",
+            spec.name,
+            env_path.display(),
+            self.backend.name()
+        ));
+        self.backend
+            .create_venv(build_path, python.map(String::as_str))?;
+        staging.check_interrupted()?;
+        self.say(format_args!(
+            "Environment '{}' created successfully.",
+            spec.name
+        ));
+
+        if !packages.is_empty() {
+            self.say(format_args!(
+                "Installing package(s) [{}] into environment '{}'...",
+                packages.join(", "),
+                spec.name
+            ));
+            self.backend.install(
+                build_path,
+                &InstallRequest {
+                    index_args: self.config.index_args(),
+                    ..InstallRequest::packages(packages)
+                },
+            )?;
+            self.say(format_args!(
+                "Package(s) installed successfully in '{}'.",
+                spec.name
+            ));
+        }
+        if let Some(after) = history::snapshot(self.backend(), build_path) {
+            let entry = HistoryEntry::new(utils::current_command_line(), Vec::new(), after);
+            history::append(build_path, &entry)?;
+        }
+        staging.commit(&env_path)?;
+        Ok(Self::env_info(RootedEnv {
+            name: spec.name.clone(),
+            path: env_path,
+            root: root.name.clone(),
+            shadowed_by: None,
+        }))
+    }
+
+    /// Install into an existing environment, recording the change in its history.
+    pub fn install(&self, env: &str, request: &InstallRequest) -> Result<()> {
+        let _lock = self.lock(env, LockMode::Exclusive)?;
+        let env_path = self.env(env)?.path;
+        history::record(self.backend(), &env_path, || {
+            self.backend.install(&env_path, request)
+        })
+    }
+
+    /// The packages installed in an environment.
+    pub fn freeze(&self, env: &str) -> Result<Vec<Requirement>> {
+        Ok(requirements::parse_requirements(&self.freeze_text(env)?))
+    }
+
+    /// The environment's packages as a requirements file, exactly as the backend
+    /// reports them (including editable installs, which [`Muv::freeze`] leaves out).
+    pub fn freeze_text(&self, env: &str) -> Result<String> {
+        let _lock = self.lock(env, LockMode::Shared)?;
+        let env_path = self.env(env)?.path;
+        self.backend.freeze(&env_path)
+    }
+
+    /// Uninstall packages (and those listed in `requirements` files) from an
+    /// environment, recording the change in its history.
+    pub fn uninstall(&self, env: &str, packages: &[String], requirements: &[String]) -> Result<()> {
+        let _lock = self.lock(env, LockMode::Exclusive)?;
+        let env_path = self.env(env)?.path;
+        history::record(self.backend(), &env_path, || {
+            self.backend.uninstall(&env_path, packages, requirements)
+        })
+    }
+
+    /// Installed packages with a newer version available from the index.
+    pub fn outdated(&self, env: &str, index_args: &[String]) -> Result<Vec<OutdatedPackage>> {
+        let _lock = self.lock(env, LockMode::Shared)?;
+        let env_path = self.env(env)?.path;
+        self.backend.list_outdated(&env_path, index_args)
+    }
+
+    /// Upgrade `packages` (every installed package when `None`) and return what
+    /// changed. The change is recorded in the environment's history.
+    pub fn upgrade(
+        &self,
+        env: &str,
+        packages: Option<&[String]>,
+        index_args: &[String],
+    ) -> Result<RequirementsDiff> {
+        let _lock = self.lock(env, LockMode::Exclusive)?;
+        let env_path = self.env(env)?.path;
+        let before_freeze = self.backend.freeze(&env_path)?;
+        let before = requirements::parse_requirements(&before_freeze);
+        let targets: Vec<String> = match packages {
+            Some(packages) => packages.to_vec(),
+            None => before.iter().map(|r| r.name.clone()).collect(),
+        };
+        if targets.is_empty() {
+            return Ok(RequirementsDiff::default());
+        }
+
+        self.say(format_args!(
+            "Upgrading package(s) [{}] in environment '{}'...",
+            targets.join(", "),
+            env
+        ));
+        let request = InstallRequest {
+            upgrade: true,
+            index_args: index_args.to_vec(),
+            ..InstallRequest::packages(targets)
+        };
+        self.backend.install(&env_path, &request)?;
+
+        let after_freeze = self.backend.freeze(&env_path)?;
+        history::append(
+            &env_path,
+            &HistoryEntry::new(
+                utils::current_command_line(),
+                history::freeze_lines(&before_freeze),
+                history::freeze_lines(&after_freeze),
+            ),
+        )?;
+        Ok(requirements::diff_requirements(
+            &before,
+            &requirements::parse_requirements(&after_freeze),
+        ))
+    }
+
+    /// The operations recorded for an environment, oldest first.
+    pub fn history(&self, env: &str) -> Result<Vec<HistoryEntry>> {
+        history::load(&self.env(env)?.path)
+    }
+
+    /// Restore the packages of an environment as they were right after history entry
+    /// `to`, or by default undo the most recent operation. Returns what changed.
+    pub fn rollback(&self, env: &str, to: Option<usize>) -> Result<RequirementsDiff> {
+        let _lock = self.lock(env, LockMode::Exclusive)?;
+        let env_path = self.env(env)?.path;
+        let entries = history::load(&env_path)?;
+        let target = match (to, entries.last()) {
+            (_, None) => return Err(MuvError::NoHistory(env.to_string())),
+            (Some(index), _) => {
+                let entry = entries
+                    .get(index)
+                    .ok_or_else(|| MuvError::HistoryEntryNotFound {
+                        env: env.to_string(),
+                        index,
+                        len: entries.len(),
+                    })?;
+                self.say(format_args!(
+                    "Restoring '{}' to its state after entry {}: {}",
+                    env, index, entry.command
+                ));
+                entry.after.clone()
+            }
+            (None, Some(last)) => {
+                self.say(format_args!("Undoing '{}' in '{}'", last.command, env));
+                last.before.clone()
+            }
+        };
+
+        let current = requirements::parse_requirements(&self.backend.freeze(&env_path)?);
+        let wanted = requirements::parse_requirements(&target.join("\n"));
+        let diff = requirements::diff_requirements(&current, &wanted);
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+
+        let meta_dir = utils::get_env_meta_dir(&env_path);
+        fs::create_dir_all(&meta_dir)?;
+        let requirements_file = meta_dir.join("rollback-requirements.txt");
+        let mut content = target.join("\n");
+        content.push('\n');
+        fs::write(&requirements_file, content)?;
+        let result = history::record(self.backend(), &env_path, || {
+            self.backend.sync(&env_path, &requirements_file)
+        });
+        let _ = fs::remove_file(&requirements_file);
+        result?;
+        Ok(diff)
+    }
+
+    /// Replace the tags of an environment (see [`EnvInfo::tags`]).
+    pub fn set_tags(&self, env: &str, tags: &[String]) -> Result<()> {
+        utils::write_env_tags(&self.env(env)?.path, tags)
+    }
+
+    /// Delete an environment: move it to the trash, from where [`Muv::restore`] can
+    /// bring it back, or with `permanent` remove it right away. Returns the trash
+    /// entry, if it went there.
+    pub fn delete(&self, env: &str, permanent: bool) -> Result<Option<TrashEntry>> {
+        // Fails if the environment was deleted while waiting for the lock.
+        let _lock = self.lock(env, LockMode::Exclusive)?;
+        let env_path = self.env(env)?.path;
+        if permanent {
+            fs::remove_dir_all(&env_path)?;
+            return Ok(None);
+        }
+        trash::move_to_trash(&self.home, env, &env_path).map(Some)
+    }
+
+    /// Deleted environments in the trash, oldest first.
+    pub fn trash(&self) -> Result<Vec<TrashEntry>> {
+        trash::list(&self.home)
+    }
+
+    /// Put the most recently deleted environment called `name` back where it was.
+    pub fn restore(&self, name: &str) -> Result<EnvInfo> {
+        utils::check_env_lookup_name(name)?;
+        let entry = self
+            .trash()?
+            .into_iter()
+            .rfind(|entry| entry.info.name == name)
+            .ok_or_else(|| MuvError::NotInTrash(name.to_string()))?;
+        trash::restore(&entry)?;
+        self.env(name)
+    }
+
+    /// Remove environments from the trash for good: all of them, or those deleted
+    /// more than `older_than` ago. Returns how many were removed.
+    pub fn empty_trash(&self, older_than: Option<Duration>) -> Result<usize> {
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for entry in self.trash()? {
+            let age = now.duration_since(entry.deleted_at()).unwrap_or_default();
+            if older_than.is_some_and(|min_age| age <= min_age) {
+                continue;
+            }
+            trash::remove(&entry)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    fn env_info(env: RootedEnv) -> EnvInfo {
+        EnvInfo {
+            python_version: utils::get_env_python_version(&env.path),
+            tags: utils::read_env_tags(&env.path),
+            name: env.name,
+            path: env.path,
+            root: env.root,
+            shadowed_by: env.shadowed_by,
+        }
+    }
+
+    fn say(&self, message: fmt::Arguments) {
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(output, "{}", message);
+        let _ = output.flush();
+    }
+}
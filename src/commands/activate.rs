@@ -1,4 +1,7 @@
 use crate::cli::EnvNameArg;
+use crate::config::Config;
+use crate::env_vars;
+use crate::hooks::{self, HookKind};
 use crate::utils;
 use anyhow::Result;
 // std::env
@@ -6,6 +9,7 @@ use anyhow::Result;
 pub fn handle_activate_for_shell_export(args: EnvNameArg) -> Result<()> {
     let (env_path, env_name) = utils::get_active_or_specified_env(args.name.as_ref())?;
     let env_bin_path = env_path.join("bin");
+    let vars = env_vars::load(&env_path)?;
 
     // Important: These commands are for POSIX-like shells (bash, zsh).
     // Fish shell or Windows CMD/PowerShell would require different commands.
@@ -27,17 +31,22 @@ pub fn handle_activate_for_shell_export(args: EnvNameArg) -> Result<()> {
     println!("export VIRTUAL_ENV=\"{}\"", env_path.display());
     println!("export MUV_ENV_NAME=\"{}\"", env_name); // For prompt and tracking
 
-    // 4. Update PS1 (prompt)
+    // 4. Update PS1 (prompt), using the configured prompt format
     // Handle case where PS1 might be unset or empty
+    let prompt = env_vars::shell_quote(&Config::load()?.prompt_for(&env_name));
     println!(
-        "if [ -n \"${{PS1+x}}\" ]; then PS1=\"({}) $PS1\"; else PS1=\"({}) \"; fi",
-        env_name, env_name
+        "if [ -n \"${{PS1+x}}\" ]; then PS1={}\"$PS1\"; else PS1={}; fi",
+        prompt, prompt
     );
 
     // 5. Clear PYTHONHOME (common practice for venvs to avoid conflicts)
     println!("if [ -n \"${{PYTHONHOME+x}}\" ]; then unset PYTHONHOME; fi");
 
-    // 6. Define a deactivate function that can be called by typing 'deactivate'
+    // 6. Export the environment's own variables, remembering what they replace
+    print!("{}", env_vars::activate_script(&vars));
+    let keys: Vec<&str> = vars.iter().map(|(key, _)| key.as_str()).collect();
+
+    // 7. Define a deactivate function that can be called by typing 'deactivate'
     // This makes it behave more like standard virtual environments.
     println!(
         r#"
@@ -46,6 +55,9 @@ if declare -f -F deactivate > /dev/null; then
 fi
 
 deactivate() {{
+    # Run the environment's pre-deactivate hook while it is still active
+    {pre_deactivate}
+
     # Restore PS1
     if [ -n "${{MUV_OLD_PS1+x}}" ]; then
         export PS1="$MUV_OLD_PS1"
@@ -66,6 +78,8 @@ deactivate() {{
         unset _MUV_OLD_VIRTUAL_PYTHONHOME
     fi
 
+    # Restore variables set by 'muv env set' or the environment's .env file
+{restore_vars}
     unset VIRTUAL_ENV
     unset MUV_ENV_NAME
 
@@ -81,7 +95,20 @@ deactivate() {{
 
     echo "Deactivated MUV environment (via 'deactivate' function)." >&2
 }}
-"#
+"#,
+        restore_vars = env_vars::deactivate_script(&keys),
+        pre_deactivate = hooks::source_script(
+            &hooks::hook_path(&env_path, HookKind::PreDeactivate).to_string_lossy()
+        )
+        .trim_end()
+    );
+
+    // 8. Run the environment's post-activate hook, now that everything is set up
+    print!(
+        "{}",
+        hooks::source_script(
+            &hooks::hook_path(&env_path, HookKind::PostActivate).to_string_lossy()
+        )
     );
 
     // Indicate successful activation (to stderr, so it doesn't get `eval`ed)
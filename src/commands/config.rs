@@ -0,0 +1,34 @@
+use crate::cli::{ConfigArgs, ConfigCommand};
+use crate::config::{self, Config};
+use anyhow::Result;
+
+pub fn handle_config(args: &ConfigArgs) -> Result<()> {
+    // Works on the file alone, so environment variables don't leak into it. `set` and
+    // `unset` edit it as plain TOML, so a file with bad settings can be repaired.
+    match &args.command {
+        ConfigCommand::Get { key } => {
+            if let Some(value) = Config::load_file()?.get(key)? {
+                println!("{}", value);
+            }
+        }
+        ConfigCommand::Set { key, value } => {
+            let path = Config::set_in_file(key, value)?;
+            println!("Set {} in {}.", key, path.display());
+        }
+        ConfigCommand::Unset { key } => {
+            let path = Config::unset_in_file(key)?;
+            println!("Unset {} in {}.", key, path.display());
+        }
+        ConfigCommand::List => {
+            let config = Config::load_file()?;
+            for key in config::KEYS {
+                match config.get(key)? {
+                    Some(value) => println!("{} = {}", key, value),
+                    None => println!("{} (not set)", key),
+                }
+            }
+        }
+        ConfigCommand::Path => println!("{}", Config::path()?.display()),
+    }
+    Ok(())
+}
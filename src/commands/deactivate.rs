@@ -1,4 +1,7 @@
+use crate::env_vars;
+use crate::hooks::{self, HookKind};
 use anyhow::Result;
+use std::path::Path;
 
 pub fn handle_deactivate_for_shell_export() -> Result<()> {
     // These commands are designed to be run by `eval` from the shell function
@@ -10,7 +13,14 @@ pub fn handle_deactivate_for_shell_export() -> Result<()> {
     println!("    echo \"No active MUV environment detected.\" >&2");
     println!("    return 0");
     println!("fi");
-    println!("");
+    println!();
+
+    // 0. Run the environment's pre-deactivate hook while it is still active
+    let pre_deactivate = hooks::hook_path(Path::new("$VIRTUAL_ENV"), HookKind::PreDeactivate);
+    print!(
+        "{}",
+        hooks::source_script(&pre_deactivate.to_string_lossy())
+    );
 
     // 1. Restore PS1
     println!("if [ -n \"${{MUV_OLD_PS1+x}}\" ]; then");
@@ -32,11 +42,16 @@ pub fn handle_deactivate_for_shell_export() -> Result<()> {
     println!("    unset _MUV_OLD_VIRTUAL_PYTHONHOME");
     println!("fi");
 
-    // 4. Unset environment variables
+    // 4. Restore variables set by 'muv env set' or the environment's .env file
+    let keys = env_vars::activated_keys();
+    let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+    print!("{}", env_vars::deactivate_script(&keys));
+
+    // 5. Unset environment variables
     println!("unset VIRTUAL_ENV");
     println!("unset MUV_ENV_NAME");
 
-    // 5. Undefine the 'deactivate' function if it exists and was ours
+    // 6. Undefine the 'deactivate' function if it exists and was ours
     // Check for MUV_OLD_PS1 as a proxy for whether our activate was run
     println!(
         "if [ -n \"${{MUV_OLD_PS1+x}}\" ] && declare -f -F deactivate > /dev/null; then unset -f deactivate; fi"
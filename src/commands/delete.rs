@@ -1,14 +1,16 @@
 use crate::cli::DeleteArgs;
-use crate::{error::MuvError, utils};
+use crate::client::Muv;
+use crate::{error::MuvError, shims};
 use anyhow::Result;
 use std::io::{self, Write};
 
-pub fn handle_delete(args: DeleteArgs) -> Result<()> {
-    let env_path = utils::ensure_env_exists(&args.name)?;
+pub fn handle_delete(args: DeleteArgs, muv: &Muv) -> Result<()> {
+    let env_path = muv.env(&args.name)?.path;
 
-    if !args.yes {
+    if !args.yes && muv.config().confirm_delete() {
         print!(
-            "Are you sure you want to delete environment '{}' at {}? [y/N]: ",
+            "Are you sure you want to {}delete environment '{}' at {}? [y/N]: ",
+            if args.permanent { "permanently " } else { "" },
             args.name,
             env_path.display()
         );
@@ -22,8 +24,21 @@ pub fn handle_delete(args: DeleteArgs) -> Result<()> {
         }
     }
 
-    println!("Deleting environment '{}'...", args.name);
-    std::fs::remove_dir_all(&env_path).map_err(MuvError::IoError)?;
-    println!("Environment '{}' deleted successfully.", args.name);
+    // The client locks it only now, so other commands aren't held up by the prompt.
+    if args.permanent {
+        println!("Deleting environment '{}'...", args.name);
+    }
+    match muv.delete(&args.name, args.permanent)? {
+        Some(entry) => println!(
+            "Moved environment '{}' to the trash ({}). Bring it back with 'muv restore {}'.",
+            args.name, entry.id, args.name
+        ),
+        None => println!("Environment '{}' deleted successfully.", args.name),
+    }
+
+    let unexposed = shims::unexpose_env(&args.name)?;
+    if !unexposed.is_empty() {
+        println!("Removed exposed command(s): {}", unexposed.join(", "));
+    }
     Ok(())
 }
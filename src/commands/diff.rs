@@ -0,0 +1,61 @@
+use crate::cli::DiffArgs;
+use crate::client::Muv;
+use crate::requirements::{self, RequirementsDiff};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct DiffReport<'a> {
+    from: &'a str,
+    to: &'a str,
+    #[serde(flatten)]
+    diff: &'a RequirementsDiff,
+}
+
+pub fn handle_diff(args: DiffArgs, muv: &Muv) -> Result<()> {
+    let from = muv.freeze(&args.env_name)?;
+
+    let (to_label, to) = if let Some(req_file) = &args.requirements {
+        let content = std::fs::read_to_string(req_file)
+            .with_context(|| format!("Failed to read requirements file '{}'", req_file))?;
+        (req_file.clone(), requirements::parse_requirements(&content))
+    } else if let Some(other_env) = &args.other_env {
+        (other_env.clone(), muv.freeze(other_env)?)
+    } else {
+        anyhow::bail!("Specify a second environment or a requirements file with --requirements.");
+    };
+
+    let diff = requirements::diff_requirements(&from, &to);
+
+    if args.json {
+        let report = DiffReport {
+            from: &args.env_name,
+            to: &to_label,
+            diff: &diff,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Comparing '{}' -> '{}':", args.env_name, to_label);
+    if diff.is_empty() {
+        println!("No differences.");
+        return Ok(());
+    }
+    for pkg in &diff.added {
+        println!("+ {} {}", pkg.name, pkg.version);
+    }
+    for pkg in &diff.removed {
+        println!("- {} {}", pkg.name, pkg.version);
+    }
+    for pkg in &diff.changed {
+        println!("~ {} {} -> {}", pkg.name, pkg.from, pkg.to);
+    }
+    println!(
+        "{} added, {} removed, {} changed.",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::cli::{EnvVarsArgs, EnvVarsCommand};
+use crate::client::Muv;
+use crate::env_vars;
+use crate::lock::LockMode;
+use anyhow::Result;
+
+pub fn handle_env(args: EnvVarsArgs, muv: &Muv) -> Result<()> {
+    match args.command {
+        EnvVarsCommand::Set { name, vars } => {
+            let _lock = muv.lock(&name, LockMode::Exclusive)?;
+            let env_path = muv.env(&name)?.path;
+            // Nothing is set unless every key is valid.
+            for (key, _) in &vars {
+                env_vars::validate_key(key)?;
+            }
+            let mut stored = env_vars::load_stored(&env_path)?;
+            for (key, value) in &vars {
+                env_vars::set(&mut stored, key.clone(), value.clone());
+            }
+            env_vars::save_stored(&env_path, &stored)?;
+            for (key, _) in &vars {
+                println!("Set {} for '{}'.", key, name);
+            }
+        }
+        EnvVarsCommand::Unset { name, keys } => {
+            let _lock = muv.lock(&name, LockMode::Exclusive)?;
+            let env_path = muv.env(&name)?.path;
+            let mut stored = env_vars::load_stored(&env_path)?;
+            for key in &keys {
+                if stored.iter().any(|(k, _)| k == key) {
+                    println!("Unset {} for '{}'.", key, name);
+                } else {
+                    println!("{} is not set for '{}'.", key, name);
+                }
+            }
+            stored.retain(|(key, _)| !keys.contains(key));
+            env_vars::save_stored(&env_path, &stored)?;
+        }
+        EnvVarsCommand::List(args) => {
+            let (env_path, env_name) = muv.resolve_env(args.name.as_ref())?;
+            let _lock = muv.lock(&env_name, LockMode::Shared)?;
+            let vars = env_vars::load(&env_path)?;
+            if vars.is_empty() {
+                println!("No variables are set for '{}'.", env_name);
+            }
+            for (key, value) in vars {
+                println!("{}={}", key, value);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::cli::{ExposeArgs, UnexposeArgs};
+use crate::error::MuvError;
+use crate::shims;
+use crate::utils;
+use anyhow::Result;
+
+pub fn handle_expose(args: ExposeArgs) -> Result<()> {
+    let env_path = utils::ensure_env_exists(&args.name)?;
+    let shims_dir = shims::get_shims_dir()?;
+    let mut registry = shims::load_registry()?;
+
+    // Check everything first, so a collision doesn't leave a partial set of shims.
+    for command in &args.commands {
+        if command.contains(std::path::is_separator) {
+            anyhow::bail!(MuvError::NotACommandName(command.clone()));
+        }
+        if !utils::is_executable(&env_path.join("bin").join(command)) {
+            anyhow::bail!(MuvError::CommandNotFound {
+                command: command.clone(),
+                env: args.name.clone(),
+            });
+        }
+        match registry.get(command) {
+            Some(owner) if owner != &args.name && !args.force => {
+                anyhow::bail!(MuvError::CommandAlreadyExposed {
+                    command: command.clone(),
+                    owner: owner.clone(),
+                })
+            }
+            None if shims_dir.join(command).exists() && !args.force => {
+                anyhow::bail!(MuvError::ShimPathTaken(shims_dir.join(command)))
+            }
+            _ => {}
+        }
+    }
+
+    for command in &args.commands {
+        let shim_path = shims::write_shim(command, &args.name, &env_path)?;
+        if let Some(previous) = registry.insert(command.clone(), args.name.clone())
+            && previous != args.name
+        {
+            println!("Replaced '{}' from environment '{}'.", command, previous);
+        }
+        println!("Exposed '{}' at {}", command, shim_path.display());
+    }
+    shims::save_registry(&registry)?;
+
+    if !std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir == shims_dir))
+    {
+        println!(
+            "Note: {} is not on your PATH. Run 'muv init --force' or add it yourself.",
+            shims_dir.display()
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_unexpose(args: UnexposeArgs) -> Result<()> {
+    let mut registry = shims::load_registry()?;
+    if let Some(unknown) = args.commands.iter().find(|c| !registry.contains_key(*c)) {
+        anyhow::bail!(MuvError::CommandNotExposed(unknown.clone()));
+    }
+    for command in &args.commands {
+        registry.remove(command);
+        shims::remove_shim(command)?;
+        println!("Removed '{}'.", command);
+    }
+    shims::save_registry(&registry)?;
+    Ok(())
+}
+
+pub fn handle_exposed() -> Result<()> {
+    let registry = shims::load_registry()?;
+    if registry.is_empty() {
+        println!("No commands are exposed. Use 'muv expose <env> <command>' to expose one.");
+        return Ok(());
+    }
+    let width = registry.keys().map(String::len).max().unwrap_or_default();
+    for (command, env_name) in &registry {
+        println!("{:<width$}  {}", command, env_name);
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::cli::EnvNameArg;
+use crate::client::Muv;
+use crate::requirements;
+use anyhow::Result;
+
+pub fn handle_history(args: EnvNameArg, muv: &Muv) -> Result<()> {
+    let (_env_path, env_name) = muv.resolve_env(args.name.as_ref())?;
+    let entries = muv.history(&env_name)?;
+
+    if entries.is_empty() {
+        println!("No history recorded for environment '{}'.", env_name);
+        return Ok(());
+    }
+
+    println!("History for environment '{}':", env_name);
+    for (index, entry) in entries.iter().enumerate() {
+        let diff = requirements::diff_requirements(
+            &requirements::parse_requirements(&entry.before.join("\n")),
+            &requirements::parse_requirements(&entry.after.join("\n")),
+        );
+        let changes = format!(
+            "+{} -{} ~{}",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+        println!(
+            "{:>4}  {}  {:<12}  {}",
+            index,
+            humantime::format_rfc3339_seconds(entry.time()),
+            changes,
+            entry.command
+        );
+    }
+    println!(
+        "Use 'muv rollback {} --to <N>' to restore the packages after entry N.",
+        env_name
+    );
+    Ok(())
+}
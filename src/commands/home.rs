@@ -1,8 +1,7 @@
-use crate::utils;
+use crate::client::Muv;
 use anyhow::Result;
 
-pub fn handle_home() -> Result<()> {
-    let home_path = utils::get_muv_home()?;
-    println!("{}", home_path.display());
+pub fn handle_home(muv: &Muv) -> Result<()> {
+    println!("{}", muv.home().display());
     Ok(())
 }
@@ -0,0 +1,69 @@
+use crate::cli::{HooksArgs, HooksCommand};
+use crate::hooks::{self, HookKind};
+use crate::utils;
+use anyhow::{Context, Result};
+use std::fs;
+use std::process::Command;
+
+pub fn handle_hooks(args: HooksArgs) -> Result<()> {
+    match args.command {
+        HooksCommand::Edit { name, hook } => {
+            let env_path = utils::ensure_env_exists(&name)?;
+            let path = hooks::hook_path(&env_path, hook);
+            if !path.exists() {
+                fs::create_dir_all(hooks::hooks_dir(&env_path))?;
+                fs::write(&path, hooks::template(hook))?;
+            }
+
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_string());
+            let mut editor_parts = editor.split_whitespace();
+            let program = editor_parts
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No editor configured; set $EDITOR."))?;
+            let status = Command::new(program)
+                .args(editor_parts)
+                .arg(&path)
+                .status()
+                .with_context(|| format!("Failed to start editor '{}'", editor))?;
+            if !status.success() {
+                anyhow::bail!("Editor '{}' exited with {}", editor, status);
+            }
+            println!(
+                "Saved {} hook for '{}' at {}",
+                hook.file_name(),
+                name,
+                path.display()
+            );
+        }
+        HooksCommand::Remove { name, hook } => {
+            let env_path = utils::ensure_env_exists(&name)?;
+            let path = hooks::hook_path(&env_path, hook);
+            if path.exists() {
+                fs::remove_file(&path)?;
+                println!("Removed {} hook from '{}'.", hook.file_name(), name);
+            } else {
+                println!("Environment '{}' has no {} hook.", name, hook.file_name());
+            }
+        }
+        HooksCommand::List(args) => {
+            let (env_path, env_name) = utils::get_active_or_specified_env(args.name.as_ref())?;
+            let existing: Vec<HookKind> = HookKind::ALL
+                .into_iter()
+                .filter(|kind| hooks::hook_path(&env_path, *kind).exists())
+                .collect();
+            if existing.is_empty() {
+                println!("Environment '{}' has no hooks.", env_name);
+            }
+            for kind in existing {
+                println!(
+                    "{:<15} {}",
+                    kind.file_name(),
+                    hooks::hook_path(&env_path, kind).display()
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::cli::EnvNameArg;
+use crate::utils::{self, EnvSource};
+use anyhow::Result;
+
+pub fn handle_info(args: EnvNameArg) -> Result<()> {
+    let resolved = utils::resolve_env(args.name.as_ref())?;
+
+    let source = match &resolved.source {
+        EnvSource::Argument => "command line".to_string(),
+        EnvSource::Active => "active environment".to_string(),
+        EnvSource::ProjectFile(file) => file.display().to_string(),
+    };
+    let python = utils::get_env_python_version(&resolved.path).unwrap_or_else(|| "unknown".into());
+
+    println!("Name:        {}", resolved.name);
+    println!("Path:        {}", resolved.path.display());
+    println!("Python:      {}", python);
+    println!("Selected by: {}", source);
+    Ok(())
+}
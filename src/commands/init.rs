@@ -1,4 +1,6 @@
 use crate::cli::InitArgs;
+use crate::error::MuvError;
+use crate::shims;
 use anyhow::{Context, Result, bail};
 use std::env;
 use std::fs::{OpenOptions, read_to_string};
@@ -22,10 +24,7 @@ fn get_shell_config_path() -> Result<(String, PathBuf)> {
     match shell_name.as_str() {
         "bash" => Ok(("bash".to_string(), home_dir.join(".bashrc"))),
         "zsh" => Ok(("zsh".to_string(), home_dir.join(".zshrc"))),
-        _ => bail!(
-            "Unsupported shell: {}. MUV init currently supports bash and zsh.",
-            shell_name
-        ),
+        _ => bail!(MuvError::ShellUnsupported(shell_name)),
     }
 }
 
@@ -123,6 +122,12 @@ fn generate_shell_script_content() -> Result<String> {
         .replace('\\', "\\\\")
         .replace('"', "\\\"");
 
+    let shims_dir = shims::get_shims_dir()?;
+    let escaped_shims_dir = shims_dir
+        .to_string_lossy()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+
     Ok(format!(
         r#"
 {MUV_INIT_BLOCK_START}
@@ -134,6 +139,12 @@ export MUV_BINARY_PATH="{escaped_muv_binary_path}"
 
 # Source the muv functions from separate file
 [ -f "$HOME/.muv-functions.sh" ] && source "$HOME/.muv-functions.sh"
+
+# Commands exposed with 'muv expose'
+case ":$PATH:" in
+    *":{escaped_shims_dir}:"*) ;;
+    *) export PATH="{escaped_shims_dir}:$PATH" ;;
+esac
 {MUV_INIT_BLOCK_END}
 "#
     ))
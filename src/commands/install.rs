@@ -1,85 +1,104 @@
-use crate::cli::PackageManagementArgs;
+use crate::backend::InstallRequest;
+use crate::cli::InstallArgs;
+use crate::client::Muv;
+use crate::error::MuvError;
+use crate::pyproject::{self, PyProject};
 use crate::utils;
 use anyhow::Result;
+use std::path::Path;
 
-pub fn handle_install(args: PackageManagementArgs) -> Result<()> {
-    let (env_path, env_name) = utils::get_active_or_specified_env(args.env_name.as_ref())?;
+pub fn handle_install(args: InstallArgs, muv: &Muv) -> Result<()> {
+    let (env_path, env_name) = muv.resolve_env(args.packages.env_name.as_ref())?;
 
-    if let Some(req_file) = &args.requirements {
-        println!(
-            "Installing dependencies from '{}' into environment '{}'...",
-            req_file, env_name
-        );
-        let uv_cmd_args = vec!["pip", "install", "-r", req_file.as_str()];
-        utils::run_uv_command(
-            &uv_cmd_args,
-            None,
-            vec![(utils::ACTIVE_ENV_VAR, env_path.as_path())],
-        )?;
-        println!(
-            "Dependencies from '{}' installed successfully in '{}'.",
-            req_file, env_name
-        );
+    // Everything goes into a single install so that requirements files, pyproject
+    // dependencies and packages are resolved together.
+    let mut request = InstallRequest {
+        requirements: args.packages.requirements.clone(),
+        editables: args.editable.clone(),
+        constraints: args.constraints.clone(),
+        overrides: args.overrides.clone(),
+        no_deps: args.no_deps,
+        index_args: args.index.to_uv_args(muv.config()),
+        ..InstallRequest::default()
+    };
+    let mut sources = Vec::new();
+
+    for req_file in &args.packages.requirements {
+        sources.push(format!("'{}'", req_file));
     }
 
-    if let Some(req_file) = &args.toml {
-        println!(
-            "Installing dependencies from '{}' into environment '{}'...",
-            req_file, env_name
-        );
-        let toml_content = std::fs::read_to_string(req_file)?;
-        let parsed_toml: toml::Value = toml::from_str(&toml_content)?;
-        if let Some(deps) = parsed_toml
-            .get("project")
-            .and_then(|proj| proj.get("dependencies"))
-            .and_then(|d| d.as_array())
-        {
-            let dep_list: Vec<String> = deps
-                .iter()
-                .filter_map(|d| d.as_str().map(|s| s.to_string()))
-                .collect();
-            if !dep_list.is_empty() {
-                let mut uv_cmd_args = vec!["pip", "install"];
-                for dep in &dep_list {
-                    uv_cmd_args.push(dep);
-                }
-                utils::run_uv_command(
-                    &uv_cmd_args,
-                    Some(&env_path),
-                    vec![(utils::ACTIVE_ENV_VAR, env_path.as_path())],
-                )?;
-            }
-        }
-        println!(
-            "Dependencies from pyproject.toml installed successfully in '{}'.",
-            env_name
-        );
+    for path in &args.editable {
+        sources.push(format!("'{}' (editable)", path));
     }
 
-    if !args.packages.is_empty() {
-        println!(
-            "Installing package(s) [{}] into environment '{}'...",
-            args.packages.join(", "),
-            env_name
-        );
+    if let Some(toml_file) = &args.toml {
+        let pyproject = PyProject::load(Path::new(toml_file))?;
 
-        let mut uv_cmd_args = vec!["pip", "install"];
-        for pkg in &args.packages {
-            uv_cmd_args.push(pkg.as_str());
+        if let Some(required) = pyproject.requires_python()
+            && let Some(found) = utils::get_env_python_version(&env_path)
+            && !pyproject::python_satisfies(&found, required)
+        {
+            anyhow::bail!(MuvError::PythonVersionMismatch {
+                env: env_name,
+                found,
+                required: required.to_string(),
+            });
         }
 
-        utils::run_uv_command(
-            &uv_cmd_args,
-            None,
-            vec![(utils::ACTIVE_ENV_VAR, env_path.as_path())],
-        )?;
+        if args.project {
+            println!(
+                "Including project '{}' from {}",
+                pyproject.project_name().unwrap_or("<unnamed>"),
+                pyproject.root.display()
+            );
+            request
+                .packages
+                .push(pyproject.root.to_string_lossy().into_owned());
+        }
 
-        println!("Package(s) installed successfully in '{}'.", env_name);
+        request
+            .packages
+            .extend(pyproject.dependencies().iter().cloned());
+        let extras = if args.all_extras {
+            pyproject.extra_names()
+        } else {
+            args.extras.clone()
+        };
+        for extra in &extras {
+            request
+                .packages
+                .extend(pyproject.extra(extra)?.iter().cloned());
+        }
+        for group in &args.groups {
+            request.packages.extend(pyproject.group(group)?);
+        }
+        sources.push(format!("'{}'", toml_file));
     }
 
-    if args.requirements.is_none() && args.packages.is_empty() && args.toml.is_none() {
-        println!("Nothing to install. Please specify packages or --requirements or --toml.");
+    if !args.packages.packages.is_empty() {
+        request
+            .packages
+            .extend(args.packages.packages.iter().cloned());
+        sources.push(format!(
+            "package(s) [{}]",
+            args.packages.packages.join(", ")
+        ));
     }
 
+    if sources.is_empty() {
+        println!(
+            "Nothing to install. Please specify packages, --requirements, --editable or --toml."
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Installing {} into environment '{}'...",
+        sources.join(", "),
+        env_name
+    );
+    muv.install(&env_name, &request)?;
+    println!("Installed successfully in '{}'.", env_name);
+
     Ok(())
 }
@@ -1,28 +1,44 @@
-use crate::utils;
+use crate::client::Muv;
 use anyhow::Result;
 
-pub fn handle_list() -> Result<()> {
-    let envs_dir = utils::get_envs_dir()?;
-    println!("Available GUV environments (in {}):", envs_dir.display());
+pub fn handle_list(muv: &Muv, json: bool) -> Result<()> {
+    let envs = muv.list_envs()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&envs)?);
+        return Ok(());
+    }
 
-    let mut count = 0;
-    if envs_dir.exists() {
-        for entry in std::fs::read_dir(envs_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                // A simple check: does it look like a venv? (e.g., has pyvenv.cfg)
-                if path.join("pyvenv.cfg").exists() {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        println!("- {}", name);
-                        count += 1;
-                    }
+    let roots = muv.roots();
+    if roots.len() == 1 {
+        println!(
+            "Available muv environments (in {}):",
+            muv.envs_dir()?.display()
+        );
+    } else {
+        let locations: Vec<String> = roots
+            .iter()
+            .map(|root| format!("{}: {}", root.name, root.path.display()))
+            .collect();
+        println!("Available muv environments (in {}):", locations.join(", "));
+    }
+    for env in &envs {
+        let mut line = format!("- {}", env.name);
+        if !env.tags.is_empty() {
+            line.push_str(&format!(" [{}]", env.tags.join(", ")));
+        }
+        // With several roots, say where each environment comes from.
+        if roots.len() > 1 {
+            match &env.shadowed_by {
+                Some(winner) => {
+                    line.push_str(&format!("  ({}, shadowed by '{}')", env.root, winner))
                 }
+                None => line.push_str(&format!("  ({})", env.root)),
             }
         }
+        println!("{}", line);
     }
-    if count == 0 {
-        println!("No environments found. Use 'guv create <name>' to create one.");
+    if envs.is_empty() {
+        println!("No environments found. Use 'muv create <name>' to create one.");
     }
     Ok(())
 }
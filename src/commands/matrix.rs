@@ -0,0 +1,230 @@
+use crate::cli::RunArgs;
+use crate::client::Muv;
+use crate::commands::run::activated_command;
+use crate::error::MuvError;
+use crate::lock::LockMode;
+use crate::overlay;
+use crate::utils;
+use anyhow::Result;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How the command went in one environment.
+struct Outcome {
+    status: std::result::Result<ExitStatus, String>,
+    duration: Duration,
+}
+
+/// Run the command once in every environment selected by `--all` or `--tag`, up to
+/// `--jobs` at a time, then print a summary. Fails if the command failed anywhere.
+pub fn handle_run_matrix(args: &RunArgs, muv: &Muv) -> Result<()> {
+    let envs = select_envs(args)?;
+    if envs.is_empty() && args.all {
+        anyhow::bail!(MuvError::NoEnvironments);
+    }
+    if envs.is_empty() {
+        anyhow::bail!(MuvError::NoTaggedEnvironments(args.tags.clone()));
+    }
+
+    eprintln!(
+        "Running in {} environment(s): {}",
+        envs.len(),
+        args.command_and_args.join(" ")
+    );
+
+    let name_width = envs.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<Option<Outcome>>> = Mutex::new(envs.iter().map(|_| None).collect());
+    let jobs = (args.jobs as usize).min(envs.len());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some((name, path)) = envs.get(index) else {
+                        break;
+                    };
+                    let prefix = format!("[{:<name_width$}] ", name);
+                    let outcome = run_in_env(args, muv, name, path, &prefix);
+                    outcomes.lock().unwrap()[index] = Some(outcome);
+                }
+            });
+        }
+    });
+    let outcomes: Vec<Outcome> = outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+
+    print_summary(&envs, &outcomes);
+    let failed = outcomes
+        .iter()
+        .filter(|o| !matches!(o.status, Ok(status) if status.success()))
+        .count();
+    if failed > 0 {
+        anyhow::bail!(MuvError::MatrixFailed {
+            failed,
+            total: envs.len(),
+        });
+    }
+    Ok(())
+}
+
+fn select_envs(args: &RunArgs) -> Result<Vec<(String, PathBuf)>> {
+    let envs = utils::list_envs()?;
+    if args.all {
+        return Ok(envs);
+    }
+    Ok(envs
+        .into_iter()
+        .filter(|(_, path)| {
+            utils::read_env_tags(path)
+                .iter()
+                .any(|tag| args.tags.contains(tag))
+        })
+        .collect())
+}
+
+fn run_in_env(args: &RunArgs, muv: &Muv, env_name: &str, env_path: &Path, prefix: &str) -> Outcome {
+    let start = Instant::now();
+    let status =
+        spawn_and_wait(args, muv, env_name, env_path, prefix).map_err(|e| format!("{:#}", e));
+    Outcome {
+        status,
+        duration: start.elapsed(),
+    }
+}
+
+fn spawn_and_wait(
+    args: &RunArgs,
+    muv: &Muv,
+    env_name: &str,
+    env_path: &Path,
+    prefix: &str,
+) -> Result<ExitStatus> {
+    let (program, program_args) = args
+        .command_and_args
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No command provided to run"))?;
+    let env_lock = muv.lock(env_name, LockMode::Shared)?;
+    let overlay = if args.with.is_empty() {
+        None
+    } else {
+        Some(overlay::ensure_overlay(
+            muv.backend(),
+            env_path,
+            &args.with,
+        )?)
+    };
+
+    let mut cmd = activated_command(
+        env_path,
+        env_name,
+        overlay.as_deref(),
+        program,
+        args.cwd.as_deref(),
+    )?;
+    cmd.args(program_args);
+    for (key, value) in &args.env {
+        cmd.env(key, value);
+    }
+    env_lock.mark_held_for(&mut cmd);
+    // Several commands may run at once, so none of them gets the terminal's stdin.
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| {
+                relay_lines(stdout, prefix, |line| {
+                    let _ = io::stdout().lock().write_all(line);
+                })
+            });
+        }
+        if let Some(stderr) = stderr {
+            scope.spawn(|| {
+                relay_lines(stderr, prefix, |line| {
+                    let _ = io::stderr().lock().write_all(line);
+                })
+            });
+        }
+    });
+    Ok(child.wait()?)
+}
+
+/// Copy `reader` line by line to `write`, prefixing each line. Each line is written
+/// in one call so lines from concurrent environments don't interleave.
+fn relay_lines(reader: impl Read, prefix: &str, write: impl Fn(&[u8])) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        line.extend_from_slice(prefix.as_bytes());
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if !line.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        write(&line);
+    }
+}
+
+fn print_summary(envs: &[(String, PathBuf)], outcomes: &[Outcome]) {
+    let name_width = envs
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(std::iter::once("Environment".len()))
+        .max()
+        .unwrap_or_default();
+
+    println!();
+    println!(
+        "{:<name_width$}  {:<6}  {:<8}  Duration",
+        "Environment", "Result", "Exit"
+    );
+    for ((name, _), outcome) in envs.iter().zip(outcomes) {
+        let (result, exit) = match &outcome.status {
+            Ok(status) if status.success() => ("pass", describe_exit(status)),
+            Ok(status) => ("FAIL", describe_exit(status)),
+            Err(_) => ("ERROR", "-".to_string()),
+        };
+        print!(
+            "{:<name_width$}  {:<6}  {:<8}  {:.2}s",
+            name,
+            result,
+            exit,
+            outcome.duration.as_secs_f64()
+        );
+        match &outcome.status {
+            Err(message) => println!("  ({})", message),
+            Ok(_) => println!(),
+        }
+    }
+}
+
+fn describe_exit(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return code.to_string();
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("signal {}", signal);
+        }
+    }
+    "?".to_string()
+}
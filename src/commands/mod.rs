@@ -1,12 +1,27 @@
 pub mod activate;
+pub mod config;
 pub mod create;
 pub mod deactivate;
 pub mod delete;
+pub mod diff;
+pub mod env;
+pub mod expose;
 pub mod freeze;
+pub mod history;
 pub mod home;
+pub mod hooks;
+pub mod info;
 pub mod init;
 pub mod install;
 pub mod list;
+pub mod matrix;
+pub mod outdated;
 pub mod path;
+pub mod rollback;
 pub mod run;
+pub mod tag;
+pub mod trash;
 pub mod uninstall;
+pub mod upgrade;
+pub mod use_env;
+pub mod which;
@@ -0,0 +1,38 @@
+use crate::cli::OutdatedArgs;
+use crate::client::Muv;
+use anyhow::Result;
+
+pub fn handle_outdated(args: OutdatedArgs, muv: &Muv) -> Result<()> {
+    let (_env_path, env_name) = muv.resolve_env(args.env.name.as_ref())?;
+
+    let outdated = muv.outdated(&env_name, &args.index.to_uv_args(muv.config()))?;
+    if outdated.is_empty() {
+        println!("All packages in '{}' are up to date.", env_name);
+        return Ok(());
+    }
+
+    let name_width = outdated
+        .iter()
+        .map(|p| p.name.len())
+        .chain(std::iter::once("Package".len()))
+        .max()
+        .unwrap_or_default();
+    let version_width = outdated
+        .iter()
+        .map(|p| p.version.len())
+        .chain(std::iter::once("Installed".len()))
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:<name_width$}  {:<version_width$}  Latest",
+        "Package", "Installed"
+    );
+    for pkg in &outdated {
+        println!(
+            "{:<name_width$}  {:<version_width$}  {}",
+            pkg.name, pkg.version, pkg.latest_version
+        );
+    }
+    Ok(())
+}
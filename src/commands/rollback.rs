@@ -0,0 +1,16 @@
+use crate::cli::RollbackArgs;
+use crate::client::Muv;
+use crate::commands::upgrade::print_changes;
+use anyhow::Result;
+
+pub fn handle_rollback(args: RollbackArgs, muv: &Muv) -> Result<()> {
+    let (_env_path, env_name) = muv.resolve_env(args.name.as_ref())?;
+    let diff = muv.rollback(&env_name, args.to)?;
+    if diff.is_empty() {
+        println!("Environment '{}' already matches that state.", env_name);
+        return Ok(());
+    }
+    print_changes(&diff);
+    println!("Environment '{}' rolled back successfully.", env_name);
+    Ok(())
+}
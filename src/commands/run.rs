@@ -1,81 +1,260 @@
 use crate::cli::RunArgs;
+use crate::client::Muv;
+use crate::commands::matrix;
+use crate::env_vars;
+use crate::lock::{EnvLock, LockMode};
+use crate::overlay;
+use crate::script;
 use crate::utils;
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 
-pub fn handle_run(args: RunArgs) -> Result<()> {
-    let env_path = utils::ensure_env_exists(&args.env_name)?;
-    let python_exe = env_path.join("bin").join("python"); // Or Scripts\python.exe on Windows
-
-    if !python_exe.exists() {
-        anyhow::bail!(
-            "Python interpreter not found at {}. Environment might be corrupted.",
-            python_exe.display()
-        );
+/// Runs the command and exits muv with the command's exit status; only returns
+/// if the command could not be started. With `--all` or `--tag` the command runs in
+/// each selected environment instead (see [`matrix`]); with `--script`, the script
+/// runs in a cached environment built from its inline metadata.
+pub fn handle_run(args: RunArgs, muv: &Muv) -> Result<()> {
+    if args.all || !args.tags.is_empty() {
+        return matrix::handle_run_matrix(&args, muv);
     }
+    let backend = muv.backend();
+
+    // Script environments are muv's own cache, so only named environments are locked.
+    let (env_path, env_name, command_and_args, env_lock) =
+        if let Some((script, script_args)) = args.script.split_first() {
+            let source = fs::read_to_string(script)
+                .with_context(|| format!("Failed to read script '{}'", script))?;
+            let metadata = script::parse_script_metadata(&source, Path::new(script))?;
+            let (env_path, env_name) = script::ensure_script_env(backend, &metadata)?;
+            let mut command_and_args = vec!["python".to_string(), script.clone()];
+            command_and_args.extend(script_args.iter().cloned());
+            (env_path, env_name, command_and_args, None)
+        } else {
+            // An explicit name always wins here, even over an active environment.
+            let (env_path, env_name) = match &args.env_name {
+                Some(name) => (muv.env(name)?.path, name.clone()),
+                None => muv.resolve_env(None)?,
+            };
+            let env_lock = muv.lock(&env_name, LockMode::Shared)?;
+            (
+                env_path,
+                env_name,
+                args.command_and_args.clone(),
+                Some(env_lock),
+            )
+        };
 
-    let (command_to_run, command_args) = args
-        .command_and_args
+    let (command_to_run, command_args) = command_and_args
         .split_first()
         .ok_or_else(|| anyhow::anyhow!("No command provided to run"))?;
 
-    let executable_to_run: std::path::PathBuf;
-    let final_args: &[String];
-
-    // If the command is "python", use the venv's python. Otherwise, assume it's a script/command in PATH or relative.
-    // For robust behavior, we should resolve commands against the venv's bin directory first.
-    let first_command_in_venv_bin = env_path.join("bin").join(command_to_run);
-
-    if command_to_run.to_lowercase() == "python" {
-        executable_to_run = python_exe.clone();
-        final_args = command_args;
-    } else if first_command_in_venv_bin.exists() && first_command_in_venv_bin.is_file() {
-        // If 'my_script' is in venv/bin, run venv/bin/my_script
-        executable_to_run = first_command_in_venv_bin;
-        final_args = command_args;
+    let overlay = if args.with.is_empty() {
+        None
     } else {
-        // Run the command as is, relying on it being in PATH or a full path itself
-        // This means we are NOT prepending the venv's python path to the system PATH
-        // for this command. If the user wants that behavior, they should activate first.
-        // This `run` command primarily targets running Python scripts with the venv's interpreter
-        // or commands installed *into* the venv's bin.
-        // A more advanced `run` might manipulate the PATH environment variable for the subprocess.
-        executable_to_run = command_to_run.into();
-        final_args = command_args;
+        Some(overlay::ensure_overlay(backend, &env_path, &args.with)?)
+    };
+
+    let mut cmd = activated_command(
+        &env_path,
+        &env_name,
+        overlay.as_deref(),
+        command_to_run,
+        args.cwd.as_deref(),
+    )?;
+    cmd.args(command_args);
+    for (key, value) in &args.env {
+        cmd.env(key, value);
+    }
+    if let Some(env_lock) = &env_lock {
+        env_lock.mark_held_for(&mut cmd);
     }
 
-    println!(
+    // Report on stderr so the command's stdout stays clean for pipes.
+    eprintln!(
         "Running in environment '{}': {} {}",
-        args.env_name,
-        executable_to_run.display(),
-        final_args.join(" ")
+        env_name,
+        Path::new(cmd.get_program()).display(),
+        command_args.join(" ")
     );
 
-    let mut cmd = Command::new(&executable_to_run);
-    cmd.args(final_args);
-    // Crucial: Set VIRTUAL_ENV so subprocesses (like pip if the script calls it) know about the venv
-    cmd.env("VIRTUAL_ENV", &env_path);
-    // For full activation behavior, one would also need to prepend env_path/bin to PATH
-    // let current_path = std::env::var("PATH").unwrap_or_default();
-    // let new_path = format!("{}:{}" , env_path.join("bin").display(), current_path);
-    // cmd.env("PATH", new_path);
+    if args.exec {
+        return exec_command(cmd, env_lock.as_ref());
+    }
 
-    let status = cmd.status().with_context(|| {
+    let mut child = cmd.spawn().with_context(|| {
         format!(
             "Failed to execute command: '{} {}'",
-            executable_to_run.display(),
-            final_args.join(" ")
+            command_to_run,
+            command_args.join(" ")
         )
     })?;
+    let status = signals::wait_forwarding(&mut child)
+        .with_context(|| format!("Failed to wait for command '{}'", command_to_run))?;
+
+    exit_like(status)
+}
+
+#[cfg(unix)]
+fn exec_command(mut cmd: Command, env_lock: Option<&EnvLock>) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    // The command takes over the environment's lock and holds it until it exits.
+    if let Some(env_lock) = env_lock {
+        env_lock.keep_across_exec()?;
+    }
+    // Only returns if the exec itself failed.
+    let err = cmd.exec();
+    Err(err).with_context(|| {
+        format!(
+            "Failed to exec '{}'",
+            Path::new(cmd.get_program()).display()
+        )
+    })
+}
+
+#[cfg(not(unix))]
+fn exec_command(_cmd: Command, _env_lock: Option<&EnvLock>) -> Result<()> {
+    anyhow::bail!("--exec is only supported on Unix.")
+}
 
-    if !status.success() {
-        anyhow::bail!(
-            "Command '{} {}' failed with status: {}",
-            executable_to_run.display(),
-            final_args.join(" "),
-            status
-        );
+/// Exit muv the same way the child exited: with its exit code, or by the same signal.
+fn exit_like(status: ExitStatus) -> ! {
+    if let Some(code) = status.code() {
+        std::process::exit(code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            signals::raise_default(signal);
+            std::process::exit(128 + signal);
+        }
     }
+    std::process::exit(1)
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::io;
+    use std::process::{Child, ExitStatus};
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    static CHILD_PID: AtomicI32 = AtomicI32::new(0);
 
-    Ok(())
+    extern "C" fn forward(signal: libc::c_int) {
+        let pid = CHILD_PID.load(Ordering::SeqCst);
+        if pid > 0 {
+            unsafe {
+                libc::kill(pid, signal);
+            }
+        }
+    }
+
+    /// Wait for the child while relaying termination signals to it. SIGINT and SIGQUIT
+    /// are ignored rather than forwarded: the terminal already delivers them to the
+    /// whole foreground process group, so the child sees them exactly once.
+    pub fn wait_forwarding(child: &mut Child) -> io::Result<ExitStatus> {
+        CHILD_PID.store(child.id() as i32, Ordering::SeqCst);
+        let handler = forward as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        unsafe {
+            libc::signal(libc::SIGTERM, handler);
+            libc::signal(libc::SIGHUP, handler);
+            libc::signal(libc::SIGINT, libc::SIG_IGN);
+            libc::signal(libc::SIGQUIT, libc::SIG_IGN);
+        }
+        let result = child.wait();
+        unsafe {
+            for signal in [libc::SIGTERM, libc::SIGHUP, libc::SIGINT, libc::SIGQUIT] {
+                libc::signal(signal, libc::SIG_DFL);
+            }
+        }
+        CHILD_PID.store(0, Ordering::SeqCst);
+        result
+    }
+
+    /// Terminate the current process with `signal` using its default action.
+    pub fn raise_default(signal: i32) {
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod signals {
+    use std::io;
+    use std::process::{Child, ExitStatus};
+
+    pub fn wait_forwarding(child: &mut Child) -> io::Result<ExitStatus> {
+        child.wait()
+    }
+}
+
+/// Build a `Command` that behaves as if the environment were activated: its `bin`
+/// directory comes first on PATH (so `python`, `pip` and console scripts resolve to
+/// the environment), `VIRTUAL_ENV` and `MUV_ENV_NAME` are set and `PYTHONHOME` is
+/// removed. The environment's own variables (`muv env set`, `.env`) are applied
+/// too. With an overlay, its `bin` comes first on PATH and its site-packages first on
+/// `PYTHONPATH`, but `VIRTUAL_ENV` stays the environment's: the overlay is a cache
+/// shared with later runs, so it isn't where the command should install things.
+pub fn activated_command(
+    env_path: &Path,
+    env_name: &str,
+    overlay: Option<&Path>,
+    program: &str,
+    cwd: Option<&Path>,
+) -> Result<Command> {
+    let mut path_entries: Vec<PathBuf> = overlay.iter().map(|o| o.join("bin")).collect();
+    path_entries.push(env_path.join("bin"));
+    if let Some(current_path) = std::env::var_os("PATH") {
+        path_entries.extend(std::env::split_paths(&current_path));
+    }
+    let new_path: OsString =
+        std::env::join_paths(&path_entries).context("Failed to build PATH for the environment")?;
+
+    let executable = resolve_program(program, &path_entries, cwd);
+    let mut cmd = Command::new(executable);
+    cmd.env("PATH", &new_path);
+    cmd.env(utils::ACTIVE_ENV_VAR, env_path);
+    cmd.env(utils::MUV_ACTIVE_ENV_NAME_VAR, env_name);
+    cmd.env_remove("PYTHONHOME");
+    let vars = env_vars::load(env_path)?;
+    if let Some(overlay_site) = overlay.and_then(utils::get_site_packages_dir) {
+        let current = match vars.iter().find(|(key, _)| key == "PYTHONPATH") {
+            Some((_, value)) => Some(OsString::from(value)),
+            None => std::env::var_os("PYTHONPATH"),
+        };
+        let mut entries = vec![overlay_site];
+        entries.extend(current.iter().flat_map(std::env::split_paths));
+        let python_path =
+            std::env::join_paths(entries).context("Failed to build PYTHONPATH for the overlay")?;
+        cmd.envs(vars);
+        cmd.env("PYTHONPATH", python_path);
+    } else {
+        cmd.envs(vars);
+    }
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+    Ok(cmd)
+}
+
+/// Resolve a program name against the given PATH entries, the way a shell would.
+/// Paths containing a separator are taken as-is (relative to `cwd` when given).
+fn resolve_program(program: &str, path_entries: &[PathBuf], cwd: Option<&Path>) -> PathBuf {
+    let program_path = Path::new(program);
+    if program_path.components().count() > 1 {
+        return match cwd {
+            Some(dir) if program_path.is_relative() => dir.join(program_path),
+            _ => program_path.to_path_buf(),
+        };
+    }
+    path_entries
+        .iter()
+        .map(|dir| dir.join(program))
+        .find(|candidate| utils::is_executable(candidate))
+        .unwrap_or_else(|| program_path.to_path_buf())
 }
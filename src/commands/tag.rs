@@ -0,0 +1,23 @@
+use crate::cli::TagArgs;
+use crate::client::Muv;
+use anyhow::Result;
+
+pub fn handle_tag(args: TagArgs, muv: &Muv) -> Result<()> {
+    let mut tags = muv.env(&args.name)?.tags;
+
+    if !args.tags.is_empty() {
+        if args.remove {
+            tags.retain(|tag| !args.tags.contains(tag));
+        } else {
+            tags.extend(args.tags.iter().cloned());
+        }
+        muv.set_tags(&args.name, &tags)?;
+        tags = muv.env(&args.name)?.tags;
+    }
+    if tags.is_empty() {
+        println!("Environment '{}' has no tags.", args.name);
+    } else {
+        println!("Tags of '{}': {}", args.name, tags.join(", "));
+    }
+    Ok(())
+}
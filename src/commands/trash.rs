@@ -0,0 +1,65 @@
+use crate::cli::{RestoreArgs, TrashArgs, TrashCommand};
+use crate::client::Muv;
+use crate::trash;
+use anyhow::Result;
+
+pub fn handle_restore(args: RestoreArgs, muv: &Muv) -> Result<()> {
+    let env = muv.restore(&args.name)?;
+    println!(
+        "Restored environment '{}' to {}.",
+        args.name,
+        env.path.display()
+    );
+    let older = muv
+        .trash()?
+        .iter()
+        .filter(|entry| entry.info.name == args.name)
+        .count();
+    match older {
+        0 => {}
+        1 => println!("An older copy of '{}' is still in the trash.", args.name),
+        n => println!(
+            "{} older copies of '{}' are still in the trash.",
+            n, args.name
+        ),
+    }
+    Ok(())
+}
+
+pub fn handle_trash(args: TrashArgs, muv: &Muv, json: bool) -> Result<()> {
+    let entries = muv.trash()?;
+    match args.command {
+        TrashCommand::List => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+                return Ok(());
+            }
+            if entries.is_empty() {
+                println!("The trash is empty.");
+                return Ok(());
+            }
+            println!(
+                "Deleted environments (in {}):",
+                trash::trash_dir(muv.home()).display()
+            );
+            let name_width = entries.iter().map(|e| e.info.name.len()).max().unwrap_or(0);
+            for entry in &entries {
+                println!(
+                    "- {:<name_width$}  deleted {}  from {}",
+                    entry.info.name,
+                    humantime::format_rfc3339_seconds(entry.deleted_at()),
+                    entry.info.path.display()
+                );
+            }
+        }
+        TrashCommand::Empty { older_than } => {
+            let removed = muv.empty_trash(older_than)?;
+            println!(
+                "Removed {} environment(s) from the trash; {} left.",
+                removed,
+                entries.len() - removed
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::cli::UpgradeArgs;
+use crate::client::Muv;
+use crate::requirements::{self, RequirementsDiff, normalize_name};
+use anyhow::Result;
+
+pub fn handle_upgrade(args: UpgradeArgs, muv: &Muv) -> Result<()> {
+    let (_env_path, env_name) = muv.resolve_env(args.name.as_ref())?;
+    let index_args = args.index.to_uv_args(muv.config());
+    // With --all, every installed package is a target.
+    let packages = (!args.all).then_some(args.packages.as_slice());
+
+    if args.dry_run {
+        let wanted: Option<Vec<String>> = packages.map(|packages| {
+            packages
+                .iter()
+                .filter_map(|t| requirements::parse_requirement(t))
+                .map(|r| r.normalized_name())
+                .collect()
+        });
+        let upgrades: Vec<_> = muv
+            .outdated(&env_name, &index_args)?
+            .into_iter()
+            .filter(|p| {
+                wanted
+                    .as_ref()
+                    .is_none_or(|wanted| wanted.contains(&normalize_name(&p.name)))
+            })
+            .collect();
+        if upgrades.is_empty() {
+            println!("Nothing would be upgraded in '{}'.", env_name);
+        } else {
+            println!("Would upgrade in '{}':", env_name);
+            for pkg in &upgrades {
+                println!("  {} {} -> {}", pkg.name, pkg.version, pkg.latest_version);
+            }
+        }
+        return Ok(());
+    }
+
+    let diff = muv.upgrade(&env_name, packages, &index_args)?;
+    if diff.is_empty() {
+        println!(
+            "All requested packages in '{}' are already up to date.",
+            env_name
+        );
+        return Ok(());
+    }
+    println!("Changes in '{}':", env_name);
+    print_changes(&diff);
+    Ok(())
+}
+
+/// One line per changed, added and removed package.
+pub(crate) fn print_changes(diff: &RequirementsDiff) {
+    for pkg in &diff.changed {
+        println!("  ~ {} {} -> {}", pkg.name, pkg.from, pkg.to);
+    }
+    for pkg in &diff.added {
+        println!("  + {} {}", pkg.name, pkg.version);
+    }
+    for pkg in &diff.removed {
+        println!("  - {} {}", pkg.name, pkg.version);
+    }
+}
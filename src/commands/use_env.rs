@@ -0,0 +1,21 @@
+use crate::cli::UseArgs;
+use crate::utils;
+use anyhow::{Context, Result};
+use std::fs;
+
+pub fn handle_use(args: UseArgs) -> Result<()> {
+    utils::ensure_env_exists(&args.name)?;
+
+    let cwd = std::env::current_dir().context("Failed to determine the current directory")?;
+    let project_file = cwd.join(utils::PROJECT_ENV_FILE);
+    fs::write(&project_file, format!("{}\n", args.name))
+        .with_context(|| format!("Failed to write {}", project_file.display()))?;
+
+    println!(
+        "Environment '{}' will be used for commands run in {} and its subdirectories.",
+        args.name,
+        cwd.display()
+    );
+    println!("(wrote {})", project_file.display());
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::cli::WhichArgs;
+use crate::dist_info;
+use crate::error::MuvError;
+use crate::shims;
+use crate::utils;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub fn handle_which(args: WhichArgs) -> Result<()> {
+    let command = &args.command;
+    let providers: Vec<(String, PathBuf)> = utils::list_envs()?
+        .into_iter()
+        .filter(|(_, env_path)| utils::is_executable(&env_path.join("bin").join(command)))
+        .collect();
+    if providers.is_empty() {
+        anyhow::bail!(MuvError::NoCommandProvider(command.clone()));
+    }
+
+    let winner = resolve_on_path(command)?;
+    let name_width = providers
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+
+    println!("'{}' is provided by:", command);
+    for (name, env_path) in &providers {
+        let marker = if winner.as_ref().and_then(|(_, env)| env.as_ref()) == Some(name) {
+            "*"
+        } else {
+            " "
+        };
+        let package = dist_info::owner_of_command(env_path, command)
+            .map(|dist| format!("{} {}", dist.name, dist.version))
+            .unwrap_or_else(|| "unknown package".to_string());
+        println!(
+            "{} {:<name_width$}  {}  ({})",
+            marker,
+            name,
+            env_path.join("bin").join(command).display(),
+            package
+        );
+    }
+
+    match winner {
+        Some((_, Some(env_name))) => println!(
+            "\n* '{}' currently runs from environment '{}'.",
+            command, env_name
+        ),
+        Some((path, None)) => println!(
+            "\n'{}' currently resolves to {}, outside muv.",
+            command,
+            path.display()
+        ),
+        None => println!(
+            "\n'{}' is not on your PATH; activate one of these environments or 'muv expose' it.",
+            command
+        ),
+    }
+    Ok(())
+}
+
+/// What running `command` here would execute: the first match on PATH, and the muv
+/// environment it belongs to, either directly (an activated env's `bin`) or through
+/// a shim from `muv expose`.
+fn resolve_on_path(command: &str) -> Result<Option<(PathBuf, Option<String>)>> {
+    let Some(path) = utils::find_executable(command) else {
+        return Ok(None);
+    };
+    let dir = path.parent().unwrap_or(&path);
+
+    let env_name = if dir == shims::get_shims_dir()? {
+        shims::load_registry()?.get(command).cloned()
+    } else {
+        utils::list_envs()?
+            .into_iter()
+            .find(|(_, env_path)| env_path.join("bin") == dir)
+            .map(|(name, _)| name)
+    };
+    Ok(Some((path, env_name)))
+}
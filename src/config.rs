@@ -0,0 +1,286 @@
+use crate::backend::BackendKind;
+use crate::error::{MuvError, Result};
+use crate::roots;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+/// Overrides the location of the config file.
+pub const CONFIG_VAR: &str = "MUV_CONFIG";
+/// Overrides `envs-dir`.
+pub const ENVS_DIR_VAR: &str = "MUV_ENVS_DIR";
+/// Overrides `output` (`text` or `json`).
+pub const OUTPUT_VAR: &str = "MUV_OUTPUT";
+
+/// Every key `muv config` knows, in the order `muv config list` shows them.
+pub const KEYS: &[&str] = &[
+    "python",
+    "default-packages",
+    "index-url",
+    "extra-index-url",
+    "envs-dir",
+    "prompt",
+    "output",
+    "confirm-delete",
+    "backend",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The global configuration file (`~/.config/muv/config.toml`). Every setting is
+/// optional; command-line flags win over environment variables, which win over this
+/// file, which wins over the built-in defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    /// Python for new environments when `muv create` gets no `--python`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub python: Option<String>,
+    /// Packages installed into every new environment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_packages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_index_url: Vec<String>,
+    /// Where environments live instead of `<muv home>/envs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub envs_dir: Option<PathBuf>,
+    /// Prompt prefix while an environment is active; `{name}` is its name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_delete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<BackendKind>,
+    /// Keys this version of muv doesn't know (from a newer version, or typos). They
+    /// are ignored with a warning rather than making every command fail.
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+impl Config {
+    /// `MUV_CONFIG`, or `muv/config.toml` in `$XDG_CONFIG_HOME` (default `~/.config`).
+    pub fn path() -> Result<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_VAR) {
+            return Ok(PathBuf::from(path));
+        }
+        let config_home = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => dirs::home_dir()
+                .ok_or(MuvError::HomeDirError)?
+                .join(".config"),
+        };
+        Ok(config_home.join("muv").join("config.toml"))
+    }
+
+    /// The settings in effect: the config file, overridden by environment variables.
+    pub fn load() -> Result<Config> {
+        let mut config = Self::load_file()?;
+        if let Some(dir) = env::var_os(ENVS_DIR_VAR).filter(|dir| !dir.is_empty()) {
+            config.envs_dir = Some(PathBuf::from(dir));
+        }
+        if let Ok(output) = env::var(OUTPUT_VAR)
+            && let Ok(output) = OutputFormat::from_str(&output, true)
+        {
+            config.output = Some(output);
+        }
+        Ok(config)
+    }
+
+    /// Just the config file; a missing file is an empty configuration.
+    pub fn load_file() -> Result<Config> {
+        let path = Self::path()?;
+        let config: Config = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|source| MuvError::TomlParseError {
+                path: path.clone(),
+                source,
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(e.into()),
+        };
+        // The file is loaded several times per command; one warning is enough.
+        static WARN_UNKNOWN: Once = Once::new();
+        if !config.unknown.is_empty() {
+            WARN_UNKNOWN.call_once(|| {
+                for key in config.unknown.keys() {
+                    eprintln!(
+                        "Warning: ignoring unknown key '{}' in {}.",
+                        key,
+                        path.display()
+                    );
+                }
+            });
+        }
+        Ok(config)
+    }
+
+    /// Set a key in the config file. The file is edited as plain TOML, so the rest
+    /// of it needn't be valid settings: that's how a broken file gets repaired.
+    pub fn set_in_file(key: &str, value: &str) -> Result<PathBuf> {
+        // Parse the value the same way as when loading.
+        let mut config = Config::default();
+        config.set(key, value)?;
+        let mut fresh = toml::Table::try_from(&config)?;
+        let path = Self::path()?;
+        let mut table = read_table(&path)?;
+        match fresh.remove(key) {
+            Some(value) => table.insert(key.to_string(), value),
+            // An empty list is the same as not set.
+            None => table.remove(key),
+        };
+        write_table(&path, &table)?;
+        Ok(path)
+    }
+
+    /// Remove a key from the config file, going back to the default. Unknown keys
+    /// present in the file can be removed too.
+    pub fn unset_in_file(key: &str) -> Result<PathBuf> {
+        let path = Self::path()?;
+        let mut table = read_table(&path)?;
+        if table.remove(key).is_none() {
+            check_key(key)?;
+        }
+        write_table(&path, &table)?;
+        Ok(path)
+    }
+
+    /// A setting as text, or `None` when it isn't set.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let value = match check_key(key)? {
+            "python" => self.python.clone(),
+            "default-packages" => join(&self.default_packages),
+            "index-url" => self.index_url.clone(),
+            "extra-index-url" => join(&self.extra_index_url),
+            "envs-dir" => self.envs_dir.as_ref().map(|dir| dir.display().to_string()),
+            "prompt" => self.prompt.clone(),
+            "output" => self.output.map(|output| value_name(&output)),
+            "confirm-delete" => self.confirm_delete.map(|confirm| confirm.to_string()),
+            "backend" => self.backend.map(|backend| value_name(&backend)),
+            _ => unreachable!("checked above"),
+        };
+        Ok(value)
+    }
+
+    /// Set a setting from text. Lists are comma-separated.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = |expected: &str| MuvError::InvalidConfigValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+        };
+        match check_key(key)? {
+            "python" => self.python = Some(value.to_string()),
+            "default-packages" => self.default_packages = split(value),
+            "index-url" => self.index_url = Some(value.to_string()),
+            "extra-index-url" => self.extra_index_url = split(value),
+            "envs-dir" => self.envs_dir = Some(PathBuf::from(value)),
+            "prompt" => self.prompt = Some(value.to_string()),
+            "output" => {
+                self.output =
+                    Some(OutputFormat::from_str(value, true).map_err(|_| invalid("text or json"))?)
+            }
+            "confirm-delete" => {
+                self.confirm_delete = Some(value.parse().map_err(|_| invalid("true or false"))?)
+            }
+            "backend" => {
+                self.backend =
+                    Some(BackendKind::from_str(value, true).map_err(|_| invalid("uv or pip"))?)
+            }
+            _ => unreachable!("checked above"),
+        }
+        Ok(())
+    }
+
+    /// `envs-dir` with a leading `~` expanded.
+    pub fn envs_dir(&self) -> Option<PathBuf> {
+        let dir = self.envs_dir.as_ref()?;
+        Some(roots::expand_home(&dir.to_string_lossy()))
+    }
+
+    /// The `uv pip` index arguments for the configured index URLs.
+    pub fn index_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(url) = &self.index_url {
+            args.extend(["--index-url".to_string(), url.clone()]);
+        }
+        for url in &self.extra_index_url {
+            args.extend(["--extra-index-url".to_string(), url.clone()]);
+        }
+        args
+    }
+
+    /// The prompt prefix for an active environment.
+    pub fn prompt_for(&self, env_name: &str) -> String {
+        self.prompt
+            .as_deref()
+            .unwrap_or("({name}) ")
+            .replace("{name}", env_name)
+    }
+
+    pub fn confirm_delete(&self) -> bool {
+        self.confirm_delete.unwrap_or(true)
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output.unwrap_or_default()
+    }
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    match fs::read_to_string(path) {
+        Ok(content) => content.parse().map_err(|source| MuvError::TomlParseError {
+            path: path.to_path_buf(),
+            source,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_table(path: &Path, table: &toml::Table) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string_pretty(table)?)?;
+    Ok(())
+}
+
+fn check_key(key: &str) -> Result<&str> {
+    if KEYS.contains(&key) {
+        Ok(key)
+    } else {
+        Err(MuvError::UnknownConfigKey(key.to_string()))
+    }
+}
+
+fn value_name(value: &impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn join(values: &[String]) -> Option<String> {
+    (!values.is_empty()).then(|| values.join(","))
+}
+
+fn split(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
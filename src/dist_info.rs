@@ -0,0 +1,97 @@
+use crate::utils;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// An installed distribution, identified by its `.dist-info` directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledDist {
+    pub name: String,
+    pub version: String,
+    pub dist_info: PathBuf,
+}
+
+/// All distributions installed in an environment's site-packages.
+pub fn installed_dists(env_path: &Path) -> Vec<InstalledDist> {
+    let Some(site_packages) = utils::get_site_packages_dir(env_path) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&site_packages) else {
+        return Vec::new();
+    };
+    let mut dists: Vec<InstalledDist> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let stem = file_name.strip_suffix(".dist-info")?;
+            // `name-version`; the name part never contains '-' (it is escaped to '_').
+            let (name, version) = stem.split_once('-')?;
+            Some(InstalledDist {
+                name: name.to_string(),
+                version: version.to_string(),
+                dist_info: entry.path(),
+            })
+        })
+        .collect();
+    dists.sort_by(|a, b| a.name.cmp(&b.name));
+    dists
+}
+
+/// The distribution that installed `command` into the environment's `bin` directory:
+/// the one whose `RECORD` lists the file, or else whose `entry_points.txt` declares a
+/// console or GUI script of that name.
+pub fn owner_of_command(env_path: &Path, command: &str) -> Option<InstalledDist> {
+    let site_packages = utils::get_site_packages_dir(env_path)?;
+    let target = normalize(&env_path.join("bin").join(command));
+    let dists = installed_dists(env_path);
+
+    let recorded = dists.iter().find(|dist| {
+        fs::read_to_string(dist.dist_info.join("RECORD"))
+            .map(|record| {
+                record.lines().any(|line| {
+                    let file = line.split(',').next().unwrap_or_default();
+                    !file.is_empty() && normalize(&site_packages.join(file)) == target
+                })
+            })
+            .unwrap_or(false)
+    });
+    recorded
+        .or_else(|| {
+            dists.iter().find(|dist| {
+                fs::read_to_string(dist.dist_info.join("entry_points.txt"))
+                    .map(|entry_points| declares_script(&entry_points, command))
+                    .unwrap_or(false)
+            })
+        })
+        .cloned()
+}
+
+fn declares_script(entry_points: &str, command: &str) -> bool {
+    let mut in_scripts = false;
+    for line in entry_points.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_scripts = matches!(line, "[console_scripts]" | "[gui_scripts]");
+        } else if in_scripts
+            && let Some((name, _)) = line.split_once('=')
+            && name.trim() == command
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Resolve `.` and `..` components lexically, so RECORD's relative paths
+/// (e.g. `../../../bin/black`) compare equal to the real location.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,160 @@
+use crate::error::{MuvError, Result};
+use crate::utils;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Variables set with `muv env set`, in dotenv format, inside the env's metadata dir.
+const VARS_FILE: &str = "env";
+/// A hand-edited dotenv file at the root of the environment.
+const DOTENV_FILE: &str = ".env";
+/// Shell variable listing the variables an activation set, so deactivation can undo them.
+pub const ACTIVATED_VARS_VAR: &str = "_MUV_ENV_VARS";
+const OLD_VALUE_PREFIX: &str = "_MUV_OLD_ENV_";
+
+pub fn vars_path(env_path: &Path) -> PathBuf {
+    utils::get_env_meta_dir(env_path).join(VARS_FILE)
+}
+
+pub fn dotenv_path(env_path: &Path) -> PathBuf {
+    env_path.join(DOTENV_FILE)
+}
+
+/// Variables stored with `muv env set`, in the order they were first set.
+pub fn load_stored(env_path: &Path) -> Result<Vec<(String, String)>> {
+    read_dotenv(&vars_path(env_path))
+}
+
+pub fn save_stored(env_path: &Path, vars: &[(String, String)]) -> Result<()> {
+    let path = vars_path(env_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content: String = vars
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, shell_quote(value)))
+        .collect();
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Every variable to apply for the environment: the env's `.env` file, then the
+/// variables stored with `muv env set`, which win on conflicts.
+pub fn load(env_path: &Path) -> Result<Vec<(String, String)>> {
+    let mut vars = read_dotenv(&dotenv_path(env_path))?;
+    for (key, value) in load_stored(env_path)? {
+        set(&mut vars, key, value);
+    }
+    Ok(vars)
+}
+
+/// Set `key` in `vars`, replacing an existing value in place.
+pub fn set(vars: &mut Vec<(String, String)>, key: String, value: String) {
+    match vars.iter_mut().find(|(k, _)| *k == key) {
+        Some(entry) => entry.1 = value,
+        None => vars.push((key, value)),
+    }
+}
+
+pub fn validate_key(key: &str) -> Result<()> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(MuvError::InvalidEnvVarName(key.to_string()))
+    }
+}
+
+fn read_dotenv(path: &Path) -> Result<Vec<(String, String)>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    let mut vars = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| MuvError::InvalidDotenvLine {
+                path: path.to_path_buf(),
+                line: number + 1,
+            })?;
+        let key = key.trim();
+        validate_key(key)?;
+        set(&mut vars, key.to_string(), unquote(value.trim()));
+    }
+    Ok(vars)
+}
+
+/// Strip one level of matching quotes. Single-quoted values are taken literally
+/// (with the `'\''` idiom for embedded quotes); double quotes just delimit.
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("'\\''", "'");
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return value[1..value.len() - 1].to_string();
+    }
+    value.to_string()
+}
+
+/// Quote a value for POSIX shells.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Shell commands that export `vars`, saving any values they replace.
+pub fn activate_script(vars: &[(String, String)]) -> String {
+    let mut script = String::new();
+    for (key, value) in vars {
+        script.push_str(&format!(
+            "if [ -n \"${{{key}+x}}\" ]; then export {OLD_VALUE_PREFIX}{key}=\"${key}\"; fi\n"
+        ));
+        script.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+    }
+    let keys: Vec<&str> = vars.iter().map(|(key, _)| key.as_str()).collect();
+    script.push_str(&format!(
+        "export {}=\"{}\"\n",
+        ACTIVATED_VARS_VAR,
+        keys.join(" ")
+    ));
+    script
+}
+
+/// Shell commands that undo [`activate_script`] for `keys`: restore replaced values
+/// and unset the rest. One block per variable, rather than a loop over
+/// `$_MUV_ENV_VARS`, since zsh doesn't split unquoted variables into words.
+pub fn deactivate_script(keys: &[&str]) -> String {
+    let mut script = String::new();
+    for key in keys {
+        script.push_str(&format!(
+            r#"if [ -n "${{{OLD_VALUE_PREFIX}{key}+x}}" ]; then
+    export {key}="${OLD_VALUE_PREFIX}{key}"
+    unset {OLD_VALUE_PREFIX}{key}
+else
+    unset {key}
+fi
+"#
+        ));
+    }
+    script.push_str(&format!("unset {}\n", ACTIVATED_VARS_VAR));
+    script
+}
+
+/// The variables the current shell's activation set, from `_MUV_ENV_VARS`. Anything
+/// that isn't a valid variable name is left out, since it ends up in a script.
+pub fn activated_keys() -> Vec<String> {
+    std::env::var(ACTIVATED_VARS_VAR)
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter(|key| validate_key(key).is_ok())
+        .map(str::to_string)
+        .collect()
+}
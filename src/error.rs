@@ -1,27 +1,388 @@
+use std::path::PathBuf;
 use thiserror::Error;
 
+/// Everything muv can fail with. Each kind has a stable exit code (see
+/// [`MuvError::exit_code`]) and name (see [`MuvError::kind`]) that scripts can rely on:
+///
+/// | Code | Kind                      | Meaning                                          |
+/// |------|---------------------------|--------------------------------------------------|
+/// | 1    | `error`                   | Any other failure (I/O, parse errors, ...)        |
+/// | 2    | `usage`                   | Invalid command line (reported by clap)           |
+/// | 3    | `env_not_found`           | The environment doesn't exist or none was chosen  |
+/// | 4    | `env_already_exists`      | An environment with that name already exists      |
+/// | 5    | `invalid_env_name`        | The name can't be used for an environment         |
+/// | 6    | `resolution_failed`       | The requirements can't be resolved                |
+/// | 7    | `interpreter_not_found`   | The requested Python interpreter isn't available  |
+/// | 8    | `shell_unsupported`       | The shell isn't supported                         |
+/// | 9    | `backend_failed`          | uv (or pip) failed                                |
+/// | 10   | `backend_not_installed`   | uv isn't installed                                |
+/// | 11   | `python_version_mismatch` | The environment's Python doesn't fit the project  |
+/// | 12   | `network_error`           | The package index couldn't be reached             |
+/// | 13   | `build_failed`            | A package had to be built from source and failed  |
+/// | 14   | `env_busy`                | Another muv command is using the environment      |
+/// | 15   | `env_conflict`            | The active environment conflicts with the request |
+/// | 16   | `invalid_config`          | A setting, `.muv-env`, `.env` or variable is bad  |
+/// | 17   | `unknown_extra_or_group`  | The project doesn't define the extra or group     |
 #[derive(Error, Debug)]
 pub enum MuvError {
     #[error("Environment '{0}' already exists.")]
     EnvironmentAlreadyExists(String),
     #[error("Environment '{0}' not found.")]
     EnvironmentNotFound(String),
+    #[error(
+        "No MUV environment is active, no environment name was specified and no {0} file was found."
+    )]
+    NoEnvironmentSelected(String),
+    #[error("'{name}' is not a valid environment name: {reason}.")]
+    InvalidEnvName { name: String, reason: String },
+    #[error("Could not resolve the requirements: {0}")]
+    ResolutionFailed(String),
+    #[error("Python interpreter '{0}' was not found.")]
+    InterpreterNotFound(String),
+    #[error("Unsupported shell: {0}. muv currently supports bash and zsh.")]
+    ShellUnsupported(String),
+    #[error("Network error: {0}")]
+    NetworkError(String),
+    #[error("Failed to build '{package}'{}", detail.as_ref().map(|d| format!(": {}", d)).unwrap_or_default())]
+    BuildFailed {
+        package: String,
+        detail: Option<String>,
+    },
+    #[error("Environment '{0}' is being used by another muv command.")]
+    EnvironmentBusy(String),
+    #[error("'{0}' is not a command name.")]
+    NotACommandName(String),
+    #[error("Command '{command}' was not found in environment '{env}'.")]
+    CommandNotFound { command: String, env: String },
+    #[error("Command '{command}' is already exposed from environment '{owner}'.")]
+    CommandAlreadyExposed { command: String, owner: String },
+    #[error("{} already exists and was not created by muv.", .0.display())]
+    ShimPathTaken(PathBuf),
+    #[error("Command '{0}' is not exposed by muv.")]
+    CommandNotExposed(String),
+    #[error("No environment provides '{0}'.")]
+    NoCommandProvider(String),
+    #[error("No history recorded for environment '{0}'; nothing to roll back.")]
+    NoHistory(String),
+    #[error("History entry {index} does not exist for '{env}' (it has {len} entries).")]
+    HistoryEntryNotFound {
+        env: String,
+        index: usize,
+        len: usize,
+    },
+    #[error("No environments found.")]
+    NoEnvironments,
+    #[error("No environments have the tag(s) [{}].", .0.join(", "))]
+    NoTaggedEnvironments(Vec<String>),
+    #[error("The command failed in {failed} of {total} environment(s).")]
+    MatrixFailed { failed: usize, total: usize },
+    #[error("{var} has more than one root named '{0}'.", var = crate::roots::ENVS_PATH_VAR)]
+    DuplicateRoot(String),
+    #[error("{} doesn't contain any directories.", crate::roots::ENVS_PATH_VAR)]
+    EmptyEnvsPath,
+    #[error("Unknown root '{name}'. Known roots: {}.", known.join(", "))]
+    UnknownRoot { name: String, known: Vec<String> },
+    #[error(
+        "The active environment '{name}' ({}) is shadowed by {}, which has the same name.",
+        active.display(),
+        shadowing.display()
+    )]
+    ActiveEnvShadowed {
+        name: String,
+        active: PathBuf,
+        shadowing: PathBuf,
+    },
+    #[error("No environment named '{0}' in the trash.")]
+    NotInTrash(String),
+    #[error(
+        "An environment ('{active}') is already active, but you specified a different one ('{requested}')."
+    )]
+    ActiveEnvMismatch { active: String, requested: String },
+    #[error(
+        "A virtual environment is active (VIRTUAL_ENV={}), but it is not a muv environment or MUV_ENV_NAME doesn't match it.",
+        .0.display()
+    )]
+    ForeignEnvActive(PathBuf),
+    #[error("{} does not contain an environment name.", .0.display())]
+    InvalidProjectEnvFile(PathBuf),
+    #[error("'{0}' is not a valid environment variable name.")]
+    InvalidEnvVarName(String),
+    #[error("{}:{line}: expected KEY=VALUE", path.display())]
+    InvalidDotenvLine { path: PathBuf, line: usize },
+    #[error("Unknown config key '{0}'. Known keys: {known}.", known = crate::config::KEYS.join(", "))]
+    UnknownConfigKey(String),
+    #[error("Invalid value '{value}' for '{key}': expected {expected}.")]
+    InvalidConfigValue {
+        key: String,
+        value: String,
+        expected: String,
+    },
+    #[error("Extra '{name}' is not defined in {}.", pyproject.display())]
+    UnknownExtra { name: String, pyproject: PathBuf },
+    #[error("Dependency group '{name}' is not defined in {}.", pyproject.display())]
+    UnknownDependencyGroup { name: String, pyproject: PathBuf },
+    #[error("Dependency group '{0}' includes itself.")]
+    DependencyGroupCycle(String),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("UV command failed: {0}")]
     UvCommandFailed(String),
+    #[error("Command failed: {0}")]
+    CommandFailed(String),
     #[error("Failed to determine MUV home directory.")]
     HomeDirError,
     #[error("User did not confirm deletion.")]
     DeletionNotConfirmed,
-    #[error("Failed to parse pyproject.toml: {0}")]
-    TomlParseError(#[from] toml::de::Error),
+    #[error("Failed to parse {}: {source}", path.display())]
+    TomlParseError {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
     #[error("Failed to serialize pyproject.toml: {0}")]
     TomlSerializeError(#[from] toml::ser::Error),
-    #[error("'{0}' is not installed or not in PATH.")]
+    #[error("Environment '{env}' uses Python {found}, but the project requires Python {required}.")]
+    PythonVersionMismatch {
+        env: String,
+        found: String,
+        required: String,
+    },
+    #[error(
+        "'{0}' is not installed or not in PATH. Install it, or use the pip backend with '--backend pip' (or MUV_BACKEND=pip)."
+    )]
     UvNotInstalled(String),
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
 }
 
+impl MuvError {
+    /// Stable name of the failure kind, as printed by `--json`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MuvError::EnvironmentNotFound(_)
+            | MuvError::NoEnvironmentSelected(_)
+            | MuvError::NoEnvironments
+            | MuvError::NoTaggedEnvironments(_)
+            | MuvError::NotInTrash(_) => "env_not_found",
+            MuvError::EnvironmentAlreadyExists(_) => "env_already_exists",
+            MuvError::InvalidEnvName { .. } => "invalid_env_name",
+            MuvError::ResolutionFailed(_) => "resolution_failed",
+            MuvError::InterpreterNotFound(_) => "interpreter_not_found",
+            MuvError::ShellUnsupported(_) => "shell_unsupported",
+            MuvError::NetworkError(_) => "network_error",
+            MuvError::BuildFailed { .. } => "build_failed",
+            MuvError::EnvironmentBusy(_) => "env_busy",
+            MuvError::UvCommandFailed(_) | MuvError::CommandFailed(_) => "backend_failed",
+            MuvError::UvNotInstalled(_) => "backend_not_installed",
+            MuvError::PythonVersionMismatch { .. } => "python_version_mismatch",
+            MuvError::ActiveEnvMismatch { .. }
+            | MuvError::ForeignEnvActive(_)
+            | MuvError::ActiveEnvShadowed { .. } => "env_conflict",
+            MuvError::InvalidProjectEnvFile(_)
+            | MuvError::InvalidEnvVarName(_)
+            | MuvError::InvalidDotenvLine { .. }
+            | MuvError::UnknownConfigKey(_)
+            | MuvError::InvalidConfigValue { .. }
+            | MuvError::DependencyGroupCycle(_) => "invalid_config",
+            MuvError::UnknownExtra { .. } | MuvError::UnknownDependencyGroup { .. } => {
+                "unknown_extra_or_group"
+            }
+            MuvError::IoError(_)
+            | MuvError::HomeDirError
+            | MuvError::DeletionNotConfirmed
+            | MuvError::TomlParseError { .. }
+            | MuvError::TomlSerializeError(_)
+            | MuvError::NotACommandName(_)
+            | MuvError::CommandNotFound { .. }
+            | MuvError::CommandAlreadyExposed { .. }
+            | MuvError::ShimPathTaken(_)
+            | MuvError::CommandNotExposed(_)
+            | MuvError::NoCommandProvider(_)
+            | MuvError::NoHistory(_)
+            | MuvError::HistoryEntryNotFound { .. }
+            | MuvError::MatrixFailed { .. }
+            | MuvError::DuplicateRoot(_)
+            | MuvError::EmptyEnvsPath
+            | MuvError::UnknownRoot { .. }
+            | MuvError::Anyhow(_) => "error",
+        }
+    }
+
+    /// Process exit code for this failure; see the table on [`MuvError`].
+    pub fn exit_code(&self) -> i32 {
+        match self.kind() {
+            "env_not_found" => 3,
+            "env_already_exists" => 4,
+            "invalid_env_name" => 5,
+            "resolution_failed" => 6,
+            "interpreter_not_found" => 7,
+            "shell_unsupported" => 8,
+            "backend_failed" => 9,
+            "backend_not_installed" => 10,
+            "python_version_mismatch" => 11,
+            "network_error" => 12,
+            "build_failed" => 13,
+            "env_busy" => 14,
+            "env_conflict" => 15,
+            "invalid_config" => 16,
+            "unknown_extra_or_group" => 17,
+            _ => 1,
+        }
+    }
+
+    /// What the user can do about it, if there's anything specific to suggest.
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            MuvError::EnvironmentNotFound(_) => {
+                "Run 'muv list' to see the available environments, or 'muv create <name>' to create one.".to_string()
+            }
+            MuvError::NoEnvironmentSelected(_) => {
+                "Use 'muv activate <name>', 'muv use <name>' or pass the environment name to the command.".to_string()
+            }
+            MuvError::EnvironmentAlreadyExists(name) => format!(
+                "Pick another name, or remove the existing one with 'muv delete {}'.",
+                name
+            ),
+            MuvError::InvalidEnvName { .. } => {
+                "Use letters, digits, '.', '_' and '-', not starting with '.' or '-'.".to_string()
+            }
+            MuvError::ResolutionFailed(_) => {
+                "Loosen the conflicting version constraints, or check that the packages exist on your index.".to_string()
+            }
+            MuvError::InterpreterNotFound(request) => format!(
+                "Install it (e.g. 'uv python install {}'), or use a version you have, e.g. 'muv create <name> --python 3.11'.",
+                request
+            ),
+            MuvError::NetworkError(_) => {
+                "Check your network connection, proxy settings and index URL (--index-url).".to_string()
+            }
+            MuvError::BuildFailed { package, .. } => format!(
+                "'{}' has no prebuilt wheel for this platform. Install its build requirements (a compiler, Python headers, system libraries), or pin a version that ships a wheel.",
+                package
+            ),
+            MuvError::ShellUnsupported(_) => {
+                "Run muv from bash or zsh, or set SHELL to one of them.".to_string()
+            }
+            MuvError::PythonVersionMismatch { .. } => {
+                "Create an environment with a compatible Python using 'muv create <name> --python <version>'.".to_string()
+            }
+            MuvError::EnvironmentBusy(_) => {
+                "Wait for the other command to finish, or run again without --no-wait.".to_string()
+            }
+            MuvError::CommandAlreadyExposed { .. } | MuvError::ShimPathTaken(_) => {
+                "Use --force to replace it.".to_string()
+            }
+            MuvError::CommandNotExposed(_) => {
+                "Run 'muv exposed' to see the exposed commands.".to_string()
+            }
+            MuvError::NotInTrash(_) => {
+                "Use 'muv trash list' to see what's there.".to_string()
+            }
+            MuvError::NoEnvironments => "Use 'muv create <name>' to create one.".to_string(),
+            MuvError::NoTaggedEnvironments(_) => {
+                "Use 'muv tag <env> <tag>' to tag one.".to_string()
+            }
+            MuvError::HistoryEntryNotFound { env, .. } => {
+                format!("Run 'muv history {}' to see its entries.", env)
+            }
+            MuvError::DuplicateRoot(_) => format!(
+                "Name them with 'name=path' in {}.",
+                crate::roots::ENVS_PATH_VAR
+            ),
+            MuvError::ActiveEnvShadowed { .. } => format!(
+                "Rename one of them, or list the active one's root first in {}.",
+                crate::roots::ENVS_PATH_VAR
+            ),
+            MuvError::ActiveEnvMismatch { .. } => {
+                "Deactivate the current environment, or leave out the environment name.".to_string()
+            }
+            MuvError::ForeignEnvActive(_) => {
+                "Pass a muv environment's name, or activate a muv environment.".to_string()
+            }
+            MuvError::InvalidProjectEnvFile(_) => {
+                "Put the environment's name in it, e.g. with 'muv use <name>'.".to_string()
+            }
+            MuvError::InvalidEnvVarName(_) => {
+                "Use letters, digits and '_', not starting with a digit.".to_string()
+            }
+            MuvError::UnknownConfigKey(_) => {
+                "Run 'muv config list' to see the settings.".to_string()
+            }
+            MuvError::UnknownExtra { pyproject, .. }
+            | MuvError::UnknownDependencyGroup { pyproject, .. } => {
+                format!("Check the spelling against {}.", pyproject.display())
+            }
+            MuvError::DeletionNotConfirmed => {
+                "Pass --yes to delete without confirmation.".to_string()
+            }
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+/// The [`MuvError`] behind an error from a command handler, if there is one. A
+/// typed error wrapped in [`MuvError::Anyhow`] is preferred over the wrapper.
+pub fn as_muv_error(error: &anyhow::Error) -> Option<&MuvError> {
+    let mut wrapper = None;
+    for cause in error.chain() {
+        match cause.downcast_ref::<MuvError>() {
+            Some(MuvError::Anyhow(inner)) => match as_muv_error(inner) {
+                Some(MuvError::Anyhow(_)) | None => {
+                    wrapper = wrapper.or(cause.downcast_ref::<MuvError>());
+                }
+                Some(typed) => return Some(typed),
+            },
+            Some(typed) => return Some(typed),
+            None => {}
+        }
+    }
+    wrapper
+}
+
+/// Print a failed command's error to stderr, as text or (with `--json`) as a JSON
+/// object, and return the exit code to use.
+pub fn report(error: &anyhow::Error, json: bool) -> i32 {
+    let muv_error = as_muv_error(error);
+    let kind = muv_error.map_or("error", MuvError::kind);
+    let code = muv_error.map_or(1, MuvError::exit_code);
+    let hint = muv_error.and_then(MuvError::hint);
+    let message = full_message(error);
+
+    if json {
+        let report = serde_json::json!({
+            "error": {
+                "kind": kind,
+                "code": code,
+                "message": message,
+                "hint": hint,
+            }
+        });
+        eprintln!("{}", report);
+    } else {
+        eprintln!("Error: {}", message);
+        if let Some(hint) = hint {
+            eprintln!("Hint: {}", hint);
+        }
+    }
+    code
+}
+
+/// The error followed by its causes, like `{:#}`, but leaving out causes the message
+/// before them already includes: most [`MuvError`]s show their source themselves.
+fn full_message(error: &anyhow::Error) -> String {
+    let mut message = String::new();
+    let mut previous = String::new();
+    for cause in error.chain() {
+        let text = cause.to_string();
+        if !previous.contains(&text) {
+            if !message.is_empty() {
+                message.push_str(": ");
+            }
+            message.push_str(&text);
+        }
+        previous = text;
+    }
+    message
+}
+
 pub type Result<T> = std::result::Result<T, MuvError>;
@@ -0,0 +1,114 @@
+use crate::backend::EnvBackend;
+use crate::error::{MuvError, Result};
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// One mutating operation on an environment, with the package set before and after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub command: String,
+    /// `uv pip freeze` lines before the operation.
+    pub before: Vec<String>,
+    /// `uv pip freeze` lines after the operation.
+    pub after: Vec<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(command: String, before: Vec<String>, after: Vec<String>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            timestamp,
+            command,
+            before,
+            after,
+        }
+    }
+
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + std::time::Duration::from_secs(self.timestamp)
+    }
+}
+
+pub fn history_path(env_path: &Path) -> PathBuf {
+    utils::get_env_meta_dir(env_path).join(HISTORY_FILE)
+}
+
+pub fn load(env_path: &Path) -> Result<Vec<HistoryEntry>> {
+    let path = history_path(env_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(&path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                MuvError::Anyhow(anyhow::anyhow!(
+                    "Corrupted history file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+pub fn append(env_path: &Path, entry: &HistoryEntry) -> Result<()> {
+    let path = history_path(env_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry)
+        .map_err(|e| MuvError::Anyhow(anyhow::anyhow!("Failed to serialize history: {}", e)))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Run a mutating operation and append it to the environment's history, with freeze
+/// snapshots taken before and after. History is best-effort: if a snapshot can't be
+/// taken the operation still runs, it just isn't recorded.
+pub fn record<T>(
+    backend: &dyn EnvBackend,
+    env_path: &Path,
+    op: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let before = snapshot(backend, env_path);
+    let result = op()?;
+    if let (Some(before), Some(after)) = (before, snapshot(backend, env_path)) {
+        let entry = HistoryEntry::new(utils::current_command_line(), before, after);
+        if let Err(e) = append(env_path, &entry) {
+            eprintln!("Warning: failed to record history: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+/// The environment's current package set as freeze lines.
+pub fn snapshot(backend: &dyn EnvBackend, env_path: &Path) -> Option<Vec<String>> {
+    backend
+        .freeze(env_path)
+        .ok()
+        .map(|output| freeze_lines(&output))
+}
+
+/// Split `uv pip freeze` output into non-empty lines.
+pub fn freeze_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,51 @@
+use crate::utils;
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+/// Shell scripts an environment can run around activation. They are sourced, not
+/// executed, so they can change the activating shell (export variables, define
+/// functions); write them in POSIX sh so they work in every supported shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    /// Sourced at the end of `muv activate`
+    PostActivate,
+    /// Sourced at the start of `deactivate`, while the environment is still active
+    PreDeactivate,
+}
+
+impl HookKind {
+    pub const ALL: [HookKind; 2] = [HookKind::PostActivate, HookKind::PreDeactivate];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            HookKind::PostActivate => "post-activate",
+            HookKind::PreDeactivate => "pre-deactivate",
+        }
+    }
+}
+
+pub fn hooks_dir(env_path: &Path) -> PathBuf {
+    utils::get_env_meta_dir(env_path).join("hooks")
+}
+
+pub fn hook_path(env_path: &Path, kind: HookKind) -> PathBuf {
+    hooks_dir(env_path).join(kind.file_name())
+}
+
+/// Shell code that sources the hook at `path` if it exists when the code runs.
+pub fn source_script(path: &str) -> String {
+    format!("if [ -f \"{path}\" ]; then . \"{path}\"; fi\n")
+}
+
+/// Starting content for a new hook script.
+pub fn template(kind: HookKind) -> String {
+    let when = match kind {
+        HookKind::PostActivate => "after the environment is activated",
+        HookKind::PreDeactivate => "before the environment is deactivated",
+    };
+    format!(
+        "# muv {} hook: sourced by your shell {}.\n# Use POSIX sh syntax so it works in bash and zsh.\n",
+        kind.file_name(),
+        when
+    )
+}
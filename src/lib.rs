@@ -1,7 +1,26 @@
+pub mod backend;
+pub mod cache;
 pub mod cli;
+pub mod client;
 pub mod commands;
+pub mod config;
+pub mod dist_info;
+pub mod env_vars;
 pub mod error;
+pub mod history;
+pub mod hooks;
+pub mod lock;
+pub mod overlay;
+pub mod pyproject;
+pub mod requirements;
+pub mod roots;
+pub mod script;
+pub mod shims;
+pub mod staging;
+pub mod trash;
 pub mod utils;
+pub mod uv_errors;
 
 // Re-export key components for easier use in tests
+pub use client::{EnvInfo, EnvSpec, Muv};
 pub use error::{MuvError, Result};
@@ -0,0 +1,104 @@
+use crate::error::{MuvError, Result};
+use std::env;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Set for commands muv runs while holding an environment's lock, to the path of the
+/// lock file. A muv started by such a command (e.g. `muv install` in the shell of
+/// `muv run app -- bash`) would otherwise wait forever for its own parent.
+pub const LOCKED_ENV_VAR: &str = "MUV_LOCKED_ENV";
+
+/// How an environment is being used while a lock is held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Reading or running the environment (run, freeze); any number at once.
+    Shared,
+    /// Changing or removing it (install, uninstall, delete, ...); nobody else at all.
+    Exclusive,
+}
+
+/// An advisory lock on one environment, released when dropped. Only muv itself takes
+/// these locks; they don't stop anything else from touching the environment.
+#[derive(Debug)]
+pub struct EnvLock {
+    file: File,
+    path: PathBuf,
+}
+
+/// The directory lock files live in: `<muv home>/locks`, one `<env>.lock` per
+/// environment. Lock files are never removed, since deleting one that another
+/// process is waiting on would let two processes "hold" the same lock.
+pub fn locks_dir(muv_home: &Path) -> PathBuf {
+    muv_home.join("locks")
+}
+
+/// Lock the environment called `env_name`. When another muv process holds a
+/// conflicting lock, say so on stderr and wait for it, or with `wait == false` fail
+/// with [`MuvError::EnvironmentBusy`].
+///
+/// Inside a command whose parent muv holds the lock (see [`LOCKED_ENV_VAR`]), an
+/// exclusive lock is downgraded to a shared one: the parent's shared lock still keeps
+/// other muv processes out, and it won't be released until this command is done.
+pub fn lock_env(muv_home: &Path, env_name: &str, mode: LockMode, wait: bool) -> Result<EnvLock> {
+    let dir = locks_dir(muv_home);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.lock", env_name));
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    let mode = if env::var_os(LOCKED_ENV_VAR).is_some_and(|held| Path::new(&held) == path) {
+        LockMode::Shared
+    } else {
+        mode
+    };
+
+    let attempt = match mode {
+        LockMode::Shared => file.try_lock_shared(),
+        LockMode::Exclusive => file.try_lock(),
+    };
+    match attempt {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) if !wait => {
+            return Err(MuvError::EnvironmentBusy(env_name.to_string()));
+        }
+        Err(TryLockError::WouldBlock) => {
+            eprintln!(
+                "Environment '{}' is busy (another muv command is using it), waiting...",
+                env_name
+            );
+            match mode {
+                LockMode::Shared => file.lock_shared()?,
+                LockMode::Exclusive => file.lock()?,
+            }
+        }
+        Err(TryLockError::Error(e)) => return Err(e.into()),
+    }
+    Ok(EnvLock { file, path })
+}
+
+impl EnvLock {
+    /// Tell `cmd`, to be run while this lock is held, that it is (see [`LOCKED_ENV_VAR`]).
+    pub fn mark_held_for(&self, cmd: &mut Command) {
+        cmd.env(LOCKED_ENV_VAR, &self.path);
+    }
+
+    /// Keep holding the lock in a program this process `exec`s into, until that
+    /// program exits.
+    #[cfg(unix)]
+    pub fn keep_across_exec(&self) -> Result<()> {
+        use std::os::fd::AsRawFd;
+        let fd = self.file.as_raw_fd();
+        // SAFETY: `fd` is an open descriptor owned by `self.file`.
+        let cleared = unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFD);
+            flags >= 0 && libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) >= 0
+        };
+        if !cleared {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+}
@@ -2,39 +2,68 @@ use std::io;
 
 use anyhow::Result;
 
-mod cli;
-mod commands;
-mod error;
-mod utils;
-
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
-use cli::{Cli, Commands};
+use muv::cli::{Cli, Commands};
+use muv::config::{Config, OutputFormat};
+use muv::{Muv, commands, error};
 
-fn main() -> Result<()> {
-    if let Err(e) = utils::check_uv_exists() {
-        eprintln!("Error: {}", e);
-        eprintln!("Please ensure 'uv' is installed and in your PATH.");
-        std::process::exit(1);
+fn main() {
+    let cli = Cli::parse();
+    // `--json` wins; otherwise MUV_OUTPUT or the config file's `output` decides.
+    let json =
+        cli.json || Config::load().is_ok_and(|config| config.output_format() == OutputFormat::Json);
+    if let Err(e) = run(cli, json) {
+        std::process::exit(error::report(&e, json));
     }
+}
 
-    let cli = Cli::parse();
+fn run(cli: Cli, json: bool) -> Result<()> {
+    // `muv config` only touches the config file, and doesn't need the settings in it
+    // to be valid to fix them with `set` or `unset`.
+    if let Commands::Config(args) = &cli.command {
+        return commands::config::handle_config(args);
+    }
+
+    // Backends only check for their tools when first used, so commands that don't
+    // need one (list, home, completions, ...) work without uv installed.
+    let muv = Muv::from_env(cli.backend)?.with_wait(!cli.no_wait);
 
     match cli.command {
         Commands::Init(args) => commands::init::handle_init(args),
-        Commands::Create(args) => commands::create::handle_create(args),
-        Commands::List => commands::list::handle_list(),
+        Commands::Create(args) => commands::create::handle_create(args, &muv),
+        Commands::List => commands::list::handle_list(&muv, json),
         Commands::Activate(args) => commands::activate::handle_activate_for_shell_export(args),
         Commands::Deactivate => commands::deactivate::handle_deactivate_for_shell_export(),
-        Commands::Delete(args) => commands::delete::handle_delete(args),
+        Commands::Delete(args) => commands::delete::handle_delete(args, &muv),
+        Commands::Restore(args) => commands::trash::handle_restore(args, &muv),
+        Commands::Trash(args) => commands::trash::handle_trash(args, &muv, json),
         // Commands::Add(args) => commands::add::handle_add(args),
         // Commands::Remove(args) => commands::remove::handle_remove(args),
-        Commands::Install(args) => commands::install::handle_install(args),
-        Commands::Uninstall(args) => commands::uninstall::handle_uninstall(args),
-        Commands::Freeze(args) => commands::freeze::handle_freeze(args),
+        Commands::Install(args) => commands::install::handle_install(args, &muv),
+        Commands::Uninstall(args) => commands::uninstall::handle_uninstall(args, &muv),
+        Commands::Freeze(args) => commands::freeze::handle_freeze(args, &muv),
+        Commands::Outdated(args) => commands::outdated::handle_outdated(args, &muv),
+        Commands::Upgrade(args) => commands::upgrade::handle_upgrade(args, &muv),
+        Commands::History(args) => commands::history::handle_history(args, &muv),
+        Commands::Rollback(args) => commands::rollback::handle_rollback(args, &muv),
+        Commands::Diff(mut args) => {
+            args.json = json;
+            commands::diff::handle_diff(args, &muv)
+        }
+        Commands::Info(args) => commands::info::handle_info(args),
+        Commands::Use(args) => commands::use_env::handle_use(args),
+        Commands::Env(args) => commands::env::handle_env(args, &muv),
+        Commands::Hooks(args) => commands::hooks::handle_hooks(args),
+        Commands::Expose(args) => commands::expose::handle_expose(args),
+        Commands::Unexpose(args) => commands::expose::handle_unexpose(args),
+        Commands::Exposed => commands::expose::handle_exposed(),
+        Commands::Which(args) => commands::which::handle_which(args),
+        Commands::Config(_) => unreachable!("handled above"),
+        Commands::Tag(args) => commands::tag::handle_tag(args, &muv),
         Commands::Path(args) => commands::path::handle_path(args),
-        Commands::Home => commands::home::handle_home(),
-        Commands::Run(args) => commands::run::handle_run(args),
+        Commands::Home => commands::home::handle_home(&muv),
+        Commands::Run(args) => commands::run::handle_run(args, &muv),
         Commands::Completions { shell } => {
             let mut cmd = Cli::command();
             generate(shell, &mut cmd, "muv", &mut io::stdout());
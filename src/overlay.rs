@@ -0,0 +1,98 @@
+use crate::backend::{EnvBackend, InstallRequest};
+use crate::cache;
+use crate::error::{MuvError, Result};
+use crate::requirements::normalize_name;
+use crate::utils;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BASE_PTH_FILE: &str = "_muv_base.pth";
+
+/// Return a cached overlay venv for `base_env` with `packages` installed, building it
+/// if needed. The overlay uses the base env's interpreter and sees the base env's
+/// site-packages through a `.pth` file, so nothing is installed into the base env.
+/// Once the base env's packages change, a new overlay is built against them.
+pub fn ensure_overlay(
+    backend: &dyn EnvBackend,
+    base_env: &Path,
+    packages: &[String],
+) -> Result<PathBuf> {
+    let base_freeze = backend.freeze(base_env)?;
+    let overlays_dir = get_overlays_dir()?;
+    let key = overlay_key(base_env, &base_freeze, packages);
+    cache::ensure_cached(&overlays_dir, &key, |overlay_path| {
+        eprintln!(
+            "Building overlay with [{}] at {}",
+            packages.join(", "),
+            overlays_dir.join(&key).display()
+        );
+        build_overlay(backend, base_env, &base_freeze, overlay_path, packages)
+    })
+}
+
+fn build_overlay(
+    backend: &dyn EnvBackend,
+    base_env: &Path,
+    base_freeze: &str,
+    overlay_path: &Path,
+    packages: &[String],
+) -> Result<()> {
+    let base_python = base_env.join("bin").join("python");
+    backend.create_venv(overlay_path, Some(&base_python.to_string_lossy()))?;
+
+    let base_site = utils::get_site_packages_dir(base_env).ok_or_else(|| {
+        MuvError::Anyhow(anyhow::anyhow!(
+            "Could not find site-packages in {}",
+            base_env.display()
+        ))
+    })?;
+    let overlay_site = utils::get_site_packages_dir(overlay_path).ok_or_else(|| {
+        MuvError::Anyhow(anyhow::anyhow!(
+            "Could not find site-packages in {}",
+            overlay_path.display()
+        ))
+    })?;
+    fs::write(
+        overlay_site.join(BASE_PTH_FILE),
+        format!("{}\n", base_site.display()),
+    )?;
+
+    // Pin anything the base env already has, so the overlay never shadows a base
+    // package with a different version.
+    let constraints_file = overlay_path.join("base-constraints.txt");
+    fs::write(&constraints_file, base_freeze)?;
+    let request = InstallRequest {
+        constraints: vec![constraints_file.to_string_lossy().into_owned()],
+        ..InstallRequest::packages(packages.iter().cloned())
+    };
+    backend.install(overlay_path, &request)
+}
+
+pub fn get_overlays_dir() -> Result<PathBuf> {
+    cache::cache_dir("overlays")
+}
+
+/// Cache key for an overlay: the base env's location and installed packages plus the
+/// requested packages, independent of the order they were given in.
+fn overlay_key(base_env: &Path, base_freeze: &str, packages: &[String]) -> String {
+    let mut specs: Vec<String> = packages
+        .iter()
+        .map(|p| {
+            let p = p.trim();
+            let name_end = p
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(p.len());
+            format!("{}{}", normalize_name(&p[..name_end]), &p[name_end..])
+        })
+        .collect();
+    specs.sort();
+    specs.dedup();
+
+    let base_env = base_env.to_string_lossy();
+    let base_freeze = cache::cache_key([base_freeze]);
+    cache::cache_key(
+        [&*base_env, &*base_freeze]
+            .into_iter()
+            .chain(specs.iter().map(String::as_str)),
+    )
+}
@@ -0,0 +1,219 @@
+use crate::error::{MuvError, Result};
+use crate::requirements::normalize_name;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The parts of a `pyproject.toml` that muv cares about.
+#[derive(Debug, Default, Deserialize)]
+pub struct PyProject {
+    #[serde(default)]
+    pub project: Option<Project>,
+    /// PEP 735 dependency groups.
+    #[serde(default, rename = "dependency-groups")]
+    pub dependency_groups: BTreeMap<String, Vec<DependencyGroupEntry>>,
+    /// Directory containing the `pyproject.toml`, i.e. the project root.
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Project {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default, rename = "optional-dependencies")]
+    pub optional_dependencies: BTreeMap<String, Vec<String>>,
+    #[serde(rename = "requires-python")]
+    pub requires_python: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum DependencyGroupEntry {
+    Requirement(String),
+    Include {
+        #[serde(rename = "include-group")]
+        include_group: String,
+    },
+}
+
+impl PyProject {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut pyproject: PyProject =
+            toml::from_str(&content).map_err(|source| MuvError::TomlParseError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        pyproject.root = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        Ok(pyproject)
+    }
+
+    pub fn dependencies(&self) -> &[String] {
+        self.project
+            .as_ref()
+            .map(|p| p.dependencies.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn project_name(&self) -> Option<&str> {
+        self.project.as_ref().and_then(|p| p.name.as_deref())
+    }
+
+    pub fn requires_python(&self) -> Option<&str> {
+        self.project
+            .as_ref()
+            .and_then(|p| p.requires_python.as_deref())
+    }
+
+    /// Names of all optional-dependency extras, sorted.
+    pub fn extra_names(&self) -> Vec<String> {
+        self.project
+            .as_ref()
+            .map(|p| p.optional_dependencies.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Requirements of an optional-dependency extra. Extra names are compared normalized.
+    pub fn extra(&self, name: &str) -> Result<&[String]> {
+        let wanted = normalize_name(name);
+        self.project
+            .as_ref()
+            .and_then(|p| {
+                p.optional_dependencies
+                    .iter()
+                    .find(|(extra, _)| normalize_name(extra) == wanted)
+            })
+            .map(|(_, deps)| deps.as_slice())
+            .ok_or_else(|| MuvError::UnknownExtra {
+                name: name.to_string(),
+                pyproject: self.root.join("pyproject.toml"),
+            })
+    }
+
+    /// Requirements of a dependency group, following `include-group` entries.
+    pub fn group(&self, name: &str) -> Result<Vec<String>> {
+        let mut requirements = Vec::new();
+        self.collect_group(name, &mut Vec::new(), &mut requirements)?;
+        Ok(requirements)
+    }
+
+    fn collect_group(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+        out: &mut Vec<String>,
+    ) -> Result<()> {
+        let wanted = normalize_name(name);
+        if stack.contains(&wanted) {
+            return Err(MuvError::DependencyGroupCycle(name.to_string()));
+        }
+        let entries = self
+            .dependency_groups
+            .iter()
+            .find(|(group, _)| normalize_name(group) == wanted)
+            .map(|(_, entries)| entries)
+            .ok_or_else(|| MuvError::UnknownDependencyGroup {
+                name: name.to_string(),
+                pyproject: self.root.join("pyproject.toml"),
+            })?;
+
+        stack.push(wanted);
+        for entry in entries {
+            match entry {
+                DependencyGroupEntry::Requirement(req) => out.push(req.clone()),
+                DependencyGroupEntry::Include { include_group } => {
+                    self.collect_group(include_group, stack, out)?
+                }
+            }
+        }
+        stack.pop();
+        Ok(())
+    }
+}
+
+/// Check whether a Python version (e.g. `3.11.4`) satisfies a PEP 440 specifier set
+/// such as `>=3.9,<3.13`. Only release segments are compared; pre-release and local
+/// version parts are ignored.
+pub fn python_satisfies(version: &str, specifiers: &str) -> bool {
+    let version = release_segments(version);
+    specifiers
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .all(|spec| specifier_matches(&version, spec))
+}
+
+fn specifier_matches(version: &[u64], spec: &str) -> bool {
+    let (op, target) = ["~=", "===", "==", "!=", "<=", ">=", "<", ">"]
+        .iter()
+        .find_map(|op| spec.strip_prefix(op).map(|rest| (*op, rest.trim())))
+        .unwrap_or(("==", spec));
+
+    if let Some(prefix) = target.strip_suffix(".*") {
+        let prefix = release_segments(prefix);
+        let matches = version.len() >= prefix.len() && version[..prefix.len()] == prefix[..];
+        return match op {
+            "==" => matches,
+            "!=" => !matches,
+            _ => false,
+        };
+    }
+
+    let target_segments = release_segments(target);
+    let ordering = compare_release(version, &target_segments);
+    match op {
+        "==" | "===" => ordering == Ordering::Equal,
+        "!=" => ordering != Ordering::Equal,
+        "<=" => ordering != Ordering::Greater,
+        ">=" => ordering != Ordering::Less,
+        "<" => ordering == Ordering::Less,
+        ">" => ordering == Ordering::Greater,
+        "~=" => {
+            if target_segments.len() < 2 {
+                return false;
+            }
+            let prefix = &target_segments[..target_segments.len() - 1];
+            ordering != Ordering::Less
+                && version.len() >= prefix.len()
+                && version[..prefix.len()] == prefix[..]
+        }
+        _ => false,
+    }
+}
+
+fn release_segments(version: &str) -> Vec<u64> {
+    let mut segments = Vec::new();
+    for part in version.trim().trim_start_matches('v').split('.') {
+        let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+        match digits.parse() {
+            Ok(number) => segments.push(number),
+            Err(_) => break,
+        }
+        // A segment like `0rc1` ends the release part of the version.
+        if digits.len() != part.len() {
+            break;
+        }
+    }
+    segments
+}
+
+fn compare_release(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            a.get(i)
+                .copied()
+                .unwrap_or(0)
+                .cmp(&b.get(i).copied().unwrap_or(0))
+        })
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
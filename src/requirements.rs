@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A single requirement line, as produced by `uv pip freeze` or found in a requirements file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Requirement {
+    /// Package name as written in the source (e.g. `Flask_Login`).
+    pub name: String,
+    /// Everything after the name and extras, e.g. `==2.0.1`, `>=1.0,<2` or `@ https://...`.
+    /// Empty when the requirement is unconstrained.
+    pub specifier: String,
+}
+
+impl Requirement {
+    /// The PEP 503 normalized name used for comparisons.
+    pub fn normalized_name(&self) -> String {
+        normalize_name(&self.name)
+    }
+
+    /// The pinned version if the requirement is an exact `==` pin.
+    pub fn pinned_version(&self) -> Option<&str> {
+        let version = self.specifier.strip_prefix("==")?;
+        if version.contains(',') || version.contains('*') {
+            return None;
+        }
+        Some(version.trim())
+    }
+
+    /// A human-readable form of the constraint: the pinned version if there is one,
+    /// otherwise the raw specifier (or `*` when unconstrained).
+    pub fn display_version(&self) -> &str {
+        match self.pinned_version() {
+            Some(version) => version,
+            None if self.specifier.is_empty() => "*",
+            None => &self.specifier,
+        }
+    }
+}
+
+/// Normalize a package name according to PEP 503: lowercase, with runs of
+/// `-`, `_` and `.` collapsed into a single `-`.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut last_was_separator = false;
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_was_separator {
+                normalized.push('-');
+            }
+            last_was_separator = true;
+        } else {
+            normalized.extend(c.to_lowercase());
+            last_was_separator = false;
+        }
+    }
+    normalized
+}
+
+/// Parse a single requirement line. Returns `None` for blank lines, comments and
+/// pip options such as `-r`, `-e` or `--index-url`.
+pub fn parse_requirement(line: &str) -> Option<Requirement> {
+    let line = strip_comment(line).trim();
+    if line.is_empty() || line.starts_with('-') {
+        return None;
+    }
+    // Environment markers don't matter for comparing package sets.
+    let line = line.split(';').next().unwrap_or_default().trim();
+
+    let name_end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(line.len());
+    let name = &line[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut rest = line[name_end..].trim_start();
+    // Extras (`requests[socks]`) are not part of the version constraint.
+    if rest.starts_with('[')
+        && let Some(close) = rest.find(']')
+    {
+        rest = rest[close + 1..].trim_start();
+    }
+
+    let specifier: String = if rest.starts_with('@') {
+        rest.to_string()
+    } else {
+        rest.chars().filter(|c| !c.is_whitespace()).collect()
+    };
+
+    Some(Requirement {
+        name: name.to_string(),
+        specifier,
+    })
+}
+
+/// Parse the contents of a requirements file (or `pip freeze` output).
+pub fn parse_requirements(content: &str) -> Vec<Requirement> {
+    content.lines().filter_map(parse_requirement).collect()
+}
+
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    match line.find(" #") {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+/// An installed package with a newer version available, as reported by `uv pip list --outdated`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutdatedPackage {
+    pub name: String,
+    pub version: String,
+    pub latest_version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageEntry {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedPackage {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Differences between two package sets, keyed by normalized name.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct RequirementsDiff {
+    pub added: Vec<PackageEntry>,
+    pub removed: Vec<PackageEntry>,
+    pub changed: Vec<ChangedPackage>,
+}
+
+impl RequirementsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compute what changes when going from the `from` package set to the `to` package set.
+/// Results are sorted by normalized package name.
+pub fn diff_requirements(from: &[Requirement], to: &[Requirement]) -> RequirementsDiff {
+    let index = |reqs: &[Requirement]| -> BTreeMap<String, Requirement> {
+        reqs.iter()
+            .map(|r| (r.normalized_name(), r.clone()))
+            .collect()
+    };
+    let from = index(from);
+    let to = index(to);
+
+    let mut diff = RequirementsDiff::default();
+    for (name, old) in &from {
+        match to.get(name) {
+            None => diff.removed.push(PackageEntry {
+                name: name.clone(),
+                version: old.display_version().to_string(),
+            }),
+            Some(new) if old.specifier != new.specifier => diff.changed.push(ChangedPackage {
+                name: name.clone(),
+                from: old.display_version().to_string(),
+                to: new.display_version().to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (name, new) in &to {
+        if !from.contains_key(name) {
+            diff.added.push(PackageEntry {
+                name: name.clone(),
+                version: new.display_version().to_string(),
+            });
+        }
+    }
+    diff
+}
@@ -0,0 +1,136 @@
+use crate::error::{MuvError, Result};
+use serde::Serialize;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Search path of environment roots, like `PATH`: `me=~/.muv/envs:team=/shared/envs`.
+pub const ENVS_PATH_VAR: &str = "MUV_ENVS_PATH";
+/// Name of the only root when `MUV_ENVS_PATH` isn't set.
+pub const DEFAULT_ROOT: &str = "default";
+
+/// A directory environments live in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// An environment found in one of the roots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootedEnv {
+    pub name: String,
+    pub path: PathBuf,
+    pub root: String,
+    /// The root whose environment of the same name hides this one, if any.
+    pub shadowed_by: Option<String>,
+}
+
+/// The roots from `MUV_ENVS_PATH`, or just `default_dir` when it isn't set.
+pub fn from_env(default_dir: PathBuf) -> Result<Vec<EnvRoot>> {
+    match env::var_os(ENVS_PATH_VAR).filter(|value| !value.is_empty()) {
+        Some(value) => parse_envs_path(&value),
+        None => Ok(vec![EnvRoot {
+            name: DEFAULT_ROOT.to_string(),
+            path: default_dir,
+        }]),
+    }
+}
+
+/// Parse a search path whose entries are `name=path` or a bare path, which is named
+/// after its directory.
+pub fn parse_envs_path(value: &OsStr) -> Result<Vec<EnvRoot>> {
+    let mut roots: Vec<EnvRoot> = Vec::new();
+    for entry in env::split_paths(value) {
+        let entry = entry.to_string_lossy().into_owned();
+        if entry.is_empty() {
+            continue;
+        }
+        let (name, path) = match entry.split_once('=') {
+            Some((name, path)) => (name.to_string(), expand_home(path)),
+            None => {
+                let path = expand_home(&entry);
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| entry.clone());
+                (name, path)
+            }
+        };
+        if roots.iter().any(|root| root.name == name) {
+            return Err(MuvError::DuplicateRoot(name));
+        }
+        roots.push(EnvRoot { name, path });
+    }
+    if roots.is_empty() {
+        return Err(MuvError::EmptyEnvsPath);
+    }
+    Ok(roots)
+}
+
+/// The root called `name`.
+pub fn find_root<'a>(roots: &'a [EnvRoot], name: &str) -> Result<&'a EnvRoot> {
+    roots
+        .iter()
+        .find(|root| root.name == name)
+        .ok_or_else(|| MuvError::UnknownRoot {
+            name: name.to_string(),
+            known: roots.iter().map(|root| root.name.clone()).collect(),
+        })
+}
+
+/// Every environment in every root, sorted by name and then root order. When several
+/// roots have an environment of the same name, the first root's wins and the others
+/// are shadowed.
+pub fn scan(roots: &[EnvRoot]) -> Result<Vec<RootedEnv>> {
+    let mut envs: Vec<RootedEnv> = Vec::new();
+    for root in roots {
+        // Roots that don't exist (yet) simply have no environments.
+        let Ok(entries) = fs::read_dir(&root.path) else {
+            continue;
+        };
+        let mut found = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            // A simple check: does it look like a venv? (e.g., has pyvenv.cfg)
+            if path.join("pyvenv.cfg").exists()
+                && let Some(name) = path.file_name().and_then(|n| n.to_str())
+            {
+                found.push((name.to_string(), path));
+            }
+        }
+        found.sort();
+        for (name, path) in found {
+            let shadowed_by = envs
+                .iter()
+                .find(|env| env.name == name && env.shadowed_by.is_none())
+                .map(|env| env.root.clone());
+            envs.push(RootedEnv {
+                name,
+                path,
+                root: root.name.clone(),
+                shadowed_by,
+            });
+        }
+    }
+    // Stable, so environments of the same name stay in root order.
+    envs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(envs)
+}
+
+/// The environment called `name` in the first root that has it.
+pub fn find_env<'a>(roots: &'a [EnvRoot], name: &str) -> Option<(PathBuf, &'a EnvRoot)> {
+    roots.iter().find_map(|root| {
+        let path = root.path.join(name);
+        path.join("pyvenv.cfg").exists().then_some((path, root))
+    })
+}
+
+/// `path` with a leading `~` replaced by the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (Path::new(path).strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
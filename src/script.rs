@@ -0,0 +1,99 @@
+use crate::backend::{EnvBackend, InstallRequest};
+use crate::cache;
+use crate::error::{MuvError, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The `# /// script` block of a Python script (PEP 723).
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct ScriptMetadata {
+    #[serde(rename = "requires-python")]
+    pub requires_python: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+/// Extract and parse the `script` metadata block from a script's source. A script
+/// without one has no requirements.
+pub fn parse_script_metadata(source: &str, path: &Path) -> Result<ScriptMetadata> {
+    let mut blocks = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        if line.trim_end() != "# /// script" {
+            continue;
+        }
+        let mut content = String::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let line = line.trim_end();
+            if line == "# ///" {
+                closed = true;
+                break;
+            }
+            let Some(rest) = line.strip_prefix('#') else {
+                break;
+            };
+            content.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+            content.push('\n');
+        }
+        if !closed {
+            return Err(MuvError::Anyhow(anyhow::anyhow!(
+                "Unclosed '# /// script' block in {}.",
+                path.display()
+            )));
+        }
+        blocks.push(content);
+    }
+
+    match blocks.as_slice() {
+        [] => Ok(ScriptMetadata::default()),
+        [content] => toml::from_str(content).map_err(|source| MuvError::TomlParseError {
+            path: path.to_path_buf(),
+            source,
+        }),
+        _ => Err(MuvError::Anyhow(anyhow::anyhow!(
+            "{} has more than one '# /// script' block.",
+            path.display()
+        ))),
+    }
+}
+
+pub fn get_script_envs_dir() -> Result<PathBuf> {
+    cache::cache_dir("script-envs")
+}
+
+/// Cache key for a script environment: its Python requirement and dependencies,
+/// independent of formatting and dependency order.
+pub fn script_env_key(metadata: &ScriptMetadata) -> String {
+    let mut dependencies: Vec<&str> = metadata.dependencies.iter().map(|d| d.trim()).collect();
+    dependencies.sort();
+    dependencies.dedup();
+
+    let requires_python = metadata.requires_python.as_deref().unwrap_or("").trim();
+    cache::cache_key(std::iter::once(requires_python).chain(dependencies))
+}
+
+/// Return the cached environment for scripts with this metadata, creating it if
+/// needed. Scripts with identical metadata share an environment.
+pub fn ensure_script_env(
+    backend: &dyn EnvBackend,
+    metadata: &ScriptMetadata,
+) -> Result<(PathBuf, String)> {
+    let name = format!("script-{}", script_env_key(metadata));
+    let envs_dir = get_script_envs_dir()?;
+    let env_path = cache::ensure_cached(&envs_dir, &name, |env_path| {
+        eprintln!(
+            "Creating script environment at {}",
+            envs_dir.join(&name).display()
+        );
+        backend.create_venv(env_path, metadata.requires_python.as_deref())?;
+        if metadata.dependencies.is_empty() {
+            return Ok(());
+        }
+        backend.install(
+            env_path,
+            &InstallRequest::packages(metadata.dependencies.iter().cloned()),
+        )
+    })?;
+    Ok((env_path, name))
+}
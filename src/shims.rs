@@ -0,0 +1,91 @@
+use crate::env_vars::shell_quote;
+use crate::error::{MuvError, Result};
+use crate::utils;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const REGISTRY_FILE: &str = "exposed.json";
+
+/// Directory holding the shims of exposed commands; `muv init` puts it on PATH.
+pub fn get_shims_dir() -> Result<PathBuf> {
+    let path = utils::get_muv_home()?.join("bin");
+    fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+fn registry_path() -> Result<PathBuf> {
+    Ok(utils::get_muv_home()?.join(REGISTRY_FILE))
+}
+
+/// Exposed commands, mapped to the environment each one comes from.
+pub fn load_registry() -> Result<BTreeMap<String, String>> {
+    let path = registry_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    serde_json::from_str(&fs::read_to_string(&path)?).map_err(|e| {
+        MuvError::Anyhow(anyhow::anyhow!(
+            "Corrupted exposed commands file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+pub fn save_registry(registry: &BTreeMap<String, String>) -> Result<()> {
+    let content = serde_json::to_string_pretty(registry).map_err(|e| {
+        MuvError::Anyhow(anyhow::anyhow!(
+            "Failed to serialize exposed commands: {}",
+            e
+        ))
+    })?;
+    fs::write(registry_path()?, content)?;
+    Ok(())
+}
+
+/// Write the shim for `command`, which execs the command from the environment at `env_path`.
+pub fn write_shim(command: &str, env_name: &str, env_path: &Path) -> Result<PathBuf> {
+    let target = env_path.join("bin").join(command);
+    let shim_path = get_shims_dir()?.join(command);
+    let content = format!(
+        "#!/bin/sh\n# Created by 'muv expose': runs '{}' from the muv environment '{}'.\nexec {} \"$@\"\n",
+        command,
+        env_name,
+        shell_quote(&target.to_string_lossy())
+    );
+    fs::write(&shim_path, content)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&shim_path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(shim_path)
+}
+
+pub fn remove_shim(command: &str) -> Result<()> {
+    let shim_path = get_shims_dir()?.join(command);
+    if shim_path.exists() {
+        fs::remove_file(shim_path)?;
+    }
+    Ok(())
+}
+
+/// Remove every command exposed from `env_name`, returning their names.
+pub fn unexpose_env(env_name: &str) -> Result<Vec<String>> {
+    let mut registry = load_registry()?;
+    let commands: Vec<String> = registry
+        .iter()
+        .filter(|(_, env)| *env == env_name)
+        .map(|(command, _)| command.clone())
+        .collect();
+    if commands.is_empty() {
+        return Ok(commands);
+    }
+    for command in &commands {
+        remove_shim(command)?;
+        registry.remove(command);
+    }
+    save_registry(&registry)?;
+    Ok(commands)
+}
@@ -0,0 +1,232 @@
+use crate::error::{MuvError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Directory inside each root where environments are built before being moved into
+/// place. It has no `pyvenv.cfg`, and environment names can't start with `.`, so it
+/// never shows up as an environment.
+pub const STAGING_DIR: &str = ".muv-staging";
+
+/// A directory a new environment is built in. Nothing appears under the
+/// environment's real name until [`Staging::commit`] renames it into place, so a
+/// failed or interrupted build leaves nothing half-built behind: the staging
+/// directory is removed when this is dropped without being committed, unless it was
+/// asked to be kept for debugging. A process killed by a signal doesn't get to drop
+/// it; see [`DeferredSignals`] for that.
+#[derive(Debug)]
+pub struct Staging {
+    path: PathBuf,
+    keep_on_failure: bool,
+    committed: bool,
+}
+
+impl Staging {
+    /// Start building the environment `name` for `root`. The staging directory is on
+    /// the same file system as the root, so the final rename is atomic.
+    pub fn new(root: &Path, name: &str, keep_on_failure: bool) -> Result<Self> {
+        // One directory per build, so concurrent builds (in other processes or
+        // threads) never share one.
+        static BUILDS: AtomicUsize = AtomicUsize::new(0);
+        let build = BUILDS.fetch_add(1, Ordering::Relaxed);
+        let parent = root
+            .join(STAGING_DIR)
+            .join(format!("{}-{}", std::process::id(), build));
+        // The directory is named like the environment, since venv tools use it as
+        // the default prompt.
+        let path = parent.join(name);
+        if path.exists() {
+            fs::remove_dir_all(&path)?;
+        }
+        fs::create_dir_all(&parent)?;
+        Ok(Self {
+            path,
+            keep_on_failure,
+            committed: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Fail if the process was asked to stop while building (only noticed while
+    /// [`DeferredSignals`] is alive).
+    pub fn check_interrupted(&self) -> Result<()> {
+        match signals::received() {
+            Some(_) => Err(MuvError::Anyhow(anyhow::anyhow!("Interrupted."))),
+            None => Ok(()),
+        }
+    }
+
+    /// Move the finished environment to `target`, which must not exist yet.
+    pub fn commit(mut self, target: &Path) -> Result<()> {
+        self.check_interrupted()?;
+        let name = || {
+            target
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        if target.exists() {
+            return Err(MuvError::EnvironmentAlreadyExists(name()));
+        }
+        relocate_scripts(&self.path, target)?;
+        if let Err(e) = fs::rename(&self.path, target) {
+            // Someone else created it while this one was being built.
+            if target.exists() {
+                return Err(MuvError::EnvironmentAlreadyExists(name()));
+            }
+            return Err(e.into());
+        }
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if !self.committed {
+            if self.keep_on_failure && self.path.exists() {
+                eprintln!(
+                    "Kept the partly built environment at {} (--keep-on-failure).",
+                    self.path.display()
+                );
+            } else {
+                let _ = fs::remove_dir_all(&self.path);
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            // Only succeeds once it's empty; the staging root is left for other builds.
+            let _ = fs::remove_dir(parent);
+        }
+    }
+}
+
+/// While this is alive, SIGINT, SIGTERM and SIGHUP don't kill the process straight
+/// away: a [`Staging`] build fails instead, cleans up, and the process dies from the
+/// signal once this is dropped. It changes process-wide signal handling, so only the
+/// CLI uses it; the library leaves signals alone.
+#[derive(Debug)]
+pub struct DeferredSignals(());
+
+impl DeferredSignals {
+    pub fn new() -> Self {
+        signals::defer();
+        Self(())
+    }
+}
+
+impl Default for DeferredSignals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DeferredSignals {
+    fn drop(&mut self) {
+        signals::restore();
+    }
+}
+
+/// venv tools write the environment's absolute path into its activation scripts and
+/// the `#!` line of console scripts, so point those at `target`, where the environment
+/// is about to be moved. Only those places are rewritten: other files in `bin/` may be
+/// native executables (or scripts mentioning the path on purpose) and are left alone,
+/// as is anything that isn't UTF-8.
+fn relocate_scripts(env_path: &Path, target: &Path) -> Result<()> {
+    let (Some(from), Some(to)) = (env_path.to_str(), target.to_str()) else {
+        return Ok(());
+    };
+    let Ok(entries) = fs::read_dir(env_path.join("bin")) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        // The interpreter itself is usually a symlink to the base Python.
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let Ok(content) = String::from_utf8(fs::read(entry.path())?) else {
+            continue;
+        };
+        let relocated = if entry.file_name().to_string_lossy().starts_with("activate") {
+            content.replace(from, to)
+        } else if content.starts_with("#!") {
+            relocate_shebang(&content, from, to)
+        } else {
+            continue;
+        };
+        if relocated != content {
+            fs::write(entry.path(), relocated)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace `from` in the `#!` line of a script. When the interpreter's path is too
+/// long for `#!`, the script starts with `#!/bin/sh` and an `'''exec'` line that runs
+/// the interpreter instead, so that line is rewritten too.
+fn relocate_shebang(content: &str, from: &str, to: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index == 0 || (index == 1 && line.starts_with("'''exec'")) {
+            result.push_str(&line.replace(from, to));
+        } else {
+            result.push_str(line);
+        }
+    }
+    result
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    const SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+    static RECEIVED: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn record(signal: libc::c_int) {
+        RECEIVED.store(signal, Ordering::SeqCst);
+    }
+
+    /// Note termination signals instead of dying from them. A child uv in the
+    /// foreground still gets the terminal's SIGINT and fails, which ends the build.
+    pub fn defer() {
+        RECEIVED.store(0, Ordering::SeqCst);
+        let handler = record as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        unsafe {
+            for signal in SIGNALS {
+                libc::signal(signal, handler);
+            }
+        }
+    }
+
+    pub fn received() -> Option<i32> {
+        Some(RECEIVED.load(Ordering::SeqCst)).filter(|&signal| signal != 0)
+    }
+
+    /// Go back to the default handlers, and die from a signal that came in meanwhile.
+    pub fn restore() {
+        unsafe {
+            for signal in SIGNALS {
+                libc::signal(signal, libc::SIG_DFL);
+            }
+            if let Some(signal) = received() {
+                libc::raise(signal);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod signals {
+    pub fn defer() {}
+
+    pub fn received() -> Option<i32> {
+        None
+    }
+
+    pub fn restore() {}
+}
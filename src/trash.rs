@@ -0,0 +1,173 @@
+use crate::error::{MuvError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const TRASH_DIR: &str = "trash";
+/// What was deleted and when, next to the environment in each trash entry.
+const INFO_FILE: &str = "trash.json";
+const ENV_DIR: &str = "env";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashInfo {
+    pub name: String,
+    /// Where the environment lived; `muv restore` puts it back there.
+    pub path: PathBuf,
+    /// Seconds since the Unix epoch.
+    pub deleted_at: u64,
+}
+
+/// A deleted environment in the trash.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashEntry {
+    /// Directory name in the trash, e.g. `app-20250101T120000Z`.
+    pub id: String,
+    #[serde(flatten)]
+    pub info: TrashInfo,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl TrashEntry {
+    pub fn deleted_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.info.deleted_at)
+    }
+}
+
+/// `<muv home>/trash`, one directory per deleted environment.
+pub fn trash_dir(muv_home: &Path) -> PathBuf {
+    muv_home.join(TRASH_DIR)
+}
+
+/// Move the environment `name` at `env_path` into the trash.
+pub fn move_to_trash(muv_home: &Path, name: &str, env_path: &Path) -> Result<TrashEntry> {
+    let deleted_at = SystemTime::now();
+    let stamp: String = humantime::format_rfc3339_seconds(deleted_at)
+        .to_string()
+        .chars()
+        .filter(|c| !matches!(c, '-' | ':'))
+        .collect();
+    let trash = trash_dir(muv_home);
+    fs::create_dir_all(&trash)?;
+    // Deleting the same name twice within a second gets a numbered entry.
+    let mut id = format!("{}-{}", name, stamp);
+    let mut n = 1;
+    while trash.join(&id).exists() {
+        n += 1;
+        id = format!("{}-{}.{}", name, stamp, n);
+    }
+
+    let entry = TrashEntry {
+        dir: trash.join(&id),
+        id,
+        info: TrashInfo {
+            name: name.to_string(),
+            path: env_path.to_path_buf(),
+            deleted_at: deleted_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        },
+    };
+    fs::create_dir_all(&entry.dir)?;
+    let info = serde_json::to_string_pretty(&entry.info)
+        .map_err(|e| MuvError::Anyhow(anyhow::anyhow!("Failed to serialize trash entry: {}", e)))?;
+    fs::write(entry.dir.join(INFO_FILE), info)?;
+    if let Err(e) = move_dir(env_path, &entry.dir.join(ENV_DIR)) {
+        let _ = fs::remove_dir_all(&entry.dir);
+        return Err(e.into());
+    }
+    Ok(entry)
+}
+
+/// Everything in the trash, oldest first. Entries that can't be read are skipped.
+pub fn list(muv_home: &Path) -> Result<Vec<TrashEntry>> {
+    let Ok(dirs) = fs::read_dir(trash_dir(muv_home)) else {
+        return Ok(Vec::new());
+    };
+    let mut entries = Vec::new();
+    for dir in dirs {
+        let dir = dir?.path();
+        let Some(info) = fs::read_to_string(dir.join(INFO_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<TrashInfo>(&content).ok())
+        else {
+            continue;
+        };
+        entries.push(TrashEntry {
+            id: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            info,
+            dir,
+        });
+    }
+    entries.sort_by(|a, b| (a.info.deleted_at, &a.id).cmp(&(b.info.deleted_at, &b.id)));
+    Ok(entries)
+}
+
+/// Put a trashed environment back where it was deleted from.
+pub fn restore(entry: &TrashEntry) -> Result<PathBuf> {
+    let target = &entry.info.path;
+    if target.exists() {
+        return Err(MuvError::EnvironmentAlreadyExists(entry.info.name.clone()));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_dir(&entry.dir.join(ENV_DIR), target)?;
+    fs::remove_dir_all(&entry.dir)?;
+    Ok(target.clone())
+}
+
+/// Delete a trashed environment for good.
+pub fn remove(entry: &TrashEntry) -> Result<()> {
+    fs::remove_dir_all(&entry.dir)?;
+    Ok(())
+}
+
+/// Rename `from` to `to`, or copy and delete it when they're on different file
+/// systems (environment roots need not be on the same one as the muv home).
+fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_dir(from, to) {
+                let _ = fs::remove_dir_all(to);
+                return Err(e);
+            }
+            fs::remove_dir_all(from)
+        }
+        result => result,
+    }
+}
+
+/// Copy a directory tree, keeping symlinks (like a venv's `bin/python`) as symlinks.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
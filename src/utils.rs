@@ -1,11 +1,17 @@
+use crate::config::Config;
 use crate::error::{MuvError, Result};
+use crate::roots::{self, EnvRoot};
+use crate::uv_errors;
 use std::env;
 use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 pub const ACTIVE_ENV_VAR: &str = "VIRTUAL_ENV";
 pub const MUV_ACTIVE_ENV_NAME_VAR: &str = "MUV_ENV_NAME";
+pub const PROJECT_ENV_FILE: &str = ".muv-env";
+const TAGS_FILE: &str = "tags";
 
 pub fn get_muv_home() -> Result<PathBuf> {
     // Check if MUV_HOME environment variable is set (useful for testing)
@@ -19,16 +25,116 @@ pub fn get_muv_home() -> Result<PathBuf> {
     Ok(base_dir.join(".muv"))
 }
 
+/// The directories environments live in: the roots in `MUV_ENVS_PATH`, or else just
+/// `envs-dir` from the config (or `MUV_ENVS_DIR`), else `<muv home>/envs`.
+pub fn get_env_roots() -> Result<Vec<EnvRoot>> {
+    let default_dir = match Config::load()?.envs_dir() {
+        Some(dir) => dir,
+        None => get_muv_home()?.join("envs"),
+    };
+    roots::from_env(default_dir)
+}
+
+/// The first root, where new environments go by default.
 pub fn get_envs_dir() -> Result<PathBuf> {
-    let path = get_muv_home()?.join("envs");
+    let path = get_env_roots()?.swap_remove(0).path;
     if !path.exists() {
         fs::create_dir_all(&path).map_err(MuvError::IoError)?;
     }
     Ok(path)
 }
 
+/// The environment called `name` in the first root that has it, or where it would be
+/// created in the first root.
 pub fn get_env_path(name: &str) -> Result<PathBuf> {
-    Ok(get_envs_dir()?.join(name))
+    check_env_lookup_name(name)?;
+    match roots::find_env(&get_env_roots()?, name) {
+        Some((path, _)) => Ok(path),
+        None => Ok(get_envs_dir()?.join(name)),
+    }
+}
+
+/// Environment names become directory names, so they are limited to letters, digits,
+/// '.', '_' and '-', and may not start with '.' or '-'. Only checked for new names
+/// (`muv create`); see [`check_env_lookup_name`] for existing ones.
+pub fn validate_env_name(name: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(MuvError::InvalidEnvName {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+    if name.is_empty() {
+        return invalid("it is empty");
+    }
+    if name.starts_with(['.', '-']) {
+        return invalid("it starts with '.' or '-'");
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')))
+    {
+        return invalid(&format!("it contains {:?}", c));
+    }
+    Ok(())
+}
+
+/// Names of existing environments are only required to be a single directory name:
+/// environments made before [`validate_env_name`] existed may have other characters.
+/// Anything else (e.g. `../x`) can't name an environment, so it isn't found.
+pub fn check_env_lookup_name(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(c)), None) if c == name => Ok(()),
+        _ => Err(MuvError::EnvironmentNotFound(name.to_string())),
+    }
+}
+
+/// Directory inside an environment where muv keeps its own metadata.
+pub fn get_env_meta_dir(env_path: &Path) -> PathBuf {
+    env_path.join(".muv")
+}
+
+/// All environments that can be used by name (shadowed ones left out), sorted by name.
+pub fn list_envs() -> Result<Vec<(String, PathBuf)>> {
+    Ok(roots::scan(&get_env_roots()?)?
+        .into_iter()
+        .filter(|env| env.shadowed_by.is_none())
+        .map(|env| (env.name, env.path))
+        .collect())
+}
+
+/// Tags attached to an environment with `muv tag`, sorted.
+pub fn read_env_tags(env_path: &Path) -> Vec<String> {
+    fs::read_to_string(get_env_meta_dir(env_path).join(TAGS_FILE))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn write_env_tags(env_path: &Path, tags: &[String]) -> Result<()> {
+    let meta_dir = get_env_meta_dir(env_path);
+    fs::create_dir_all(&meta_dir)?;
+    let mut tags = tags.to_vec();
+    tags.sort();
+    tags.dedup();
+    let content: String = tags.iter().map(|tag| format!("{}\n", tag)).collect();
+    fs::write(meta_dir.join(TAGS_FILE), content)?;
+    Ok(())
+}
+
+/// The command line muv was invoked with, for display and history.
+pub fn current_command_line() -> String {
+    std::iter::once("muv".to_string())
+        .chain(env::args().skip(1))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 pub fn ensure_env_exists(name: &str) -> Result<PathBuf> {
@@ -39,13 +145,67 @@ pub fn ensure_env_exists(name: &str) -> Result<PathBuf> {
     Ok(path)
 }
 
+/// The Python version recorded in the environment's `pyvenv.cfg`, if any.
+pub fn get_env_python_version(env_path: &Path) -> Option<String> {
+    let cfg = fs::read_to_string(env_path.join("pyvenv.cfg")).ok()?;
+    cfg.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "version_info" | "version").then(|| value.trim().to_string())
+    })
+}
+
+/// The `site-packages` directory of an environment (`lib/pythonX.Y/site-packages`).
+pub fn get_site_packages_dir(env_path: &Path) -> Option<PathBuf> {
+    let windows_site = env_path.join("Lib").join("site-packages");
+    if windows_site.is_dir() {
+        return Some(windows_site);
+    }
+    fs::read_dir(env_path.join("lib"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("python"))
+        .map(|entry| entry.path().join("site-packages"))
+        .find(|site| site.is_dir())
+}
+
+/// Where `program` would be run from: itself if it is a path, otherwise the first
+/// match on PATH.
+pub fn find_executable(program: &str) -> Option<PathBuf> {
+    if program.contains(std::path::is_separator) {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+/// Whether `path` is a regular file that can be executed.
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path)
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
 pub fn check_uv_exists() -> Result<()> {
     Command::new("uv")
         .arg("--version")
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .status()
-        .map_err(|e| MuvError::UvCommandFailed(format!("Failed to execute uv: {}", e)))
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => MuvError::UvNotInstalled("uv".to_string()),
+            _ => MuvError::UvCommandFailed(format!("Failed to execute uv: {}", e)),
+        })
         .and_then(|status| {
             if status.success() {
                 Ok(())
@@ -69,14 +229,115 @@ pub fn run_uv_command(
         cmd.env(key, val);
     }
 
-    // dbg!(&cmd);
-    let status = cmd
-        .status()
+    // uv's stderr is shown as it is written and kept, so a failure can be explained.
+    // When our stderr is a terminal, uv gets a pseudo-terminal of its own so it still
+    // draws progress bars. Otherwise it gets a pipe, and like any uv run with stderr
+    // redirected it leaves the progress bars out.
+    let pty = if io::stderr().is_terminal() {
+        stderr_pty()
+    } else {
+        None
+    };
+    let pty_reader = match pty {
+        Some((reader, writer)) => {
+            cmd.stderr(writer);
+            Some(reader)
+        }
+        None => {
+            cmd.stderr(Stdio::piped());
+            if io::stderr().is_terminal() {
+                cmd.env("CLICOLOR_FORCE", "1");
+            }
+            None
+        }
+    };
+    let mut child = cmd
+        .spawn()
         .map_err(|e| MuvError::UvCommandFailed(format!("Failed to execute uv: {}", e)))?;
-    // dbg!(&status);
+    // The command holds our copy of the pty's terminal end; reading only ends once
+    // every copy of it is closed.
+    drop(cmd);
+    let stderr = match pty_reader {
+        Some(reader) => tee_to_stderr(reader),
+        None => tee_to_stderr(child.stderr.take().expect("stderr is piped")),
+    };
+    let status = child.wait()?;
     if !status.success() {
         let err_msg = format!("uv {} failed with status: {}", args.join(" "), status);
-        return Err(MuvError::UvCommandFailed(err_msg));
+        // A terminal ends lines with "\r\n", and progress bars redraw with a bare "\r".
+        let stderr = stderr.replace("\r\n", "\n").replace('\r', "\n");
+        return Err(uv_errors::classify(&stderr, err_msg));
+    }
+    Ok(())
+}
+
+/// A pseudo-terminal the size of our stderr: the end to read from, and the end for
+/// the child to write to.
+#[cfg(unix)]
+fn stderr_pty() -> Option<(fs::File, fs::File)> {
+    use std::os::fd::FromRawFd;
+
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let (mut reader, mut writer) = (-1, -1);
+    // SAFETY: every pointer refers to a live local; the fds openpty returns are ours.
+    unsafe {
+        libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut size);
+        if libc::openpty(
+            &mut reader,
+            &mut writer,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &raw mut size,
+        ) != 0
+        {
+            return None;
+        }
+        for fd in [reader, writer] {
+            libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+        Some((fs::File::from_raw_fd(reader), fs::File::from_raw_fd(writer)))
+    }
+}
+
+#[cfg(not(unix))]
+fn stderr_pty() -> Option<(fs::File, fs::File)> {
+    None
+}
+
+/// Copy everything from `reader` to our stderr as it arrives, and return it. Stops
+/// at the first error too: a pty's reading end fails with EIO once the child is done.
+fn tee_to_stderr(mut reader: impl Read) -> String {
+    let mut captured = Vec::new();
+    let mut buf = [0; 4096];
+    let mut stderr = io::stderr();
+    while let Ok(n) = reader.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        let _ = stderr.write_all(&buf[..n]);
+        let _ = stderr.flush();
+        captured.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&captured).into_owned()
+}
+
+/// Run `program` with inherited stdio, failing if it exits unsuccessfully.
+pub fn run_command(program: &Path, args: &[&str], env_vars: Vec<(&str, &Path)>) -> Result<()> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    for (key, val) in env_vars {
+        cmd.env(key, val);
+    }
+    let status = cmd.status().map_err(|e| {
+        MuvError::CommandFailed(format!("Failed to execute {}: {}", program.display(), e))
+    })?;
+    if !status.success() {
+        return Err(MuvError::CommandFailed(format!(
+            "{} {} failed with status: {}",
+            program.display(),
+            args.join(" "),
+            status
+        )));
     }
     Ok(())
 }
@@ -87,6 +348,13 @@ pub fn get_command_output(
     current_dir: Option<&Path>,
     env_vars: Vec<(&str, &Path)>,
 ) -> Result<String> {
+    let failed = |msg: String| {
+        if program == "uv" {
+            MuvError::UvCommandFailed(msg)
+        } else {
+            MuvError::CommandFailed(msg)
+        }
+    };
     let mut cmd = Command::new(program);
     cmd.args(args);
     if let Some(dir) = current_dir {
@@ -98,20 +366,24 @@ pub fn get_command_output(
 
     let output = cmd
         .output()
-        .map_err(|e| MuvError::UvCommandFailed(format!("Failed to execute {}: {}", program, e)))?;
+        .map_err(|e| failed(format!("Failed to execute {}: {}", program, e)))?;
 
     if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
         let err_msg = format!(
             "{} {} failed with status: {}. Stderr: {}",
             program,
             args.join(" "),
             output.status,
-            String::from_utf8_lossy(&output.stderr)
+            stderr
         );
-        return Err(MuvError::UvCommandFailed(err_msg));
+        if program == "uv" {
+            return Err(uv_errors::classify(&stderr, err_msg));
+        }
+        return Err(failed(err_msg));
     }
     String::from_utf8(output.stdout)
-        .map_err(|e| MuvError::UvCommandFailed(format!("Failed to parse output as UTF-8: {}", e)))
+        .map_err(|e| failed(format!("Failed to parse output as UTF-8: {}", e)))
 }
 
 pub fn _create_basic_pyproject_toml(project_path: &Path) -> Result<()> {
@@ -129,43 +401,159 @@ build-backend = "hatchling.build"
     fs::write(toml_file_path, toml_content).map_err(MuvError::IoError)
 }
 
-pub fn get_active_or_specified_env(env_name_arg: Option<&String>) -> Result<(PathBuf, String)> {
+/// How the environment for a command was chosen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvSource {
+    /// Named on the command line.
+    Argument,
+    /// The currently activated muv environment.
+    Active,
+    /// Read from a `.muv-env` file in the current directory or one of its parents.
+    ProjectFile(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedEnv {
+    pub path: PathBuf,
+    pub name: String,
+    pub source: EnvSource,
+}
+
+/// Find the nearest `.muv-env` file, starting at `start` and walking up to the root.
+pub fn find_project_env_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_ENV_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Read the environment name from a `.muv-env` file: its first non-empty, non-comment line.
+pub fn read_project_env_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .ok_or_else(|| MuvError::InvalidProjectEnvFile(path.to_path_buf()))
+}
+
+/// Resolve the environment a command should operate on. In order of precedence:
+///
+/// 1. the name given on the command line (which must agree with the active
+///    environment, if one is active);
+/// 2. the active muv environment (`VIRTUAL_ENV` + `MUV_ENV_NAME`);
+/// 3. the nearest `.muv-env` file in the current directory or its parents.
+///
+/// A non-muv virtual environment being active without a name given is an error,
+/// rather than silently falling through to a `.muv-env` file.
+pub fn resolve_env(env_name_arg: Option<&String>) -> Result<ResolvedEnv> {
+    resolve_env_in(&get_env_roots()?, env_name_arg)
+}
+
+/// [`resolve_env`] with environments looked up in `roots`.
+pub fn resolve_env_in(roots: &[EnvRoot], env_name_arg: Option<&String>) -> Result<ResolvedEnv> {
+    let find = |name: &str| {
+        check_env_lookup_name(name)?;
+        roots::find_env(roots, name)
+            .map(|(path, _)| path)
+            .ok_or_else(|| MuvError::EnvironmentNotFound(name.to_string()))
+    };
     if let (Ok(active_env_path_str), Ok(active_muv_name)) =
         (env::var(ACTIVE_ENV_VAR), env::var(MUV_ACTIVE_ENV_NAME_VAR))
     {
         let active_env_path = PathBuf::from(active_env_path_str);
-        let envs_dir = get_envs_dir()?;
-        if active_env_path.starts_with(&envs_dir)
+        if roots
+            .iter()
+            .any(|root| active_env_path.starts_with(&root.path))
             && active_env_path.file_name().and_then(|s| s.to_str()) == Some(&active_muv_name)
             && active_env_path.join("pyvenv.cfg").exists()
         {
-            if let Some(name_arg) = env_name_arg {
-                if name_arg != &active_muv_name {
-                    return Err(MuvError::Anyhow(anyhow::anyhow!(
-                        "An environment ('{}') is already active, but you specified a different one ('{}').\nDeactivate the current environment or omit the environment name argument.",
-                        active_muv_name,
-                        name_arg
-                    )));
-                }
+            if let Some(name_arg) = env_name_arg
+                && name_arg != &active_muv_name
+            {
+                return Err(MuvError::ActiveEnvMismatch {
+                    active: active_muv_name,
+                    requested: name_arg.clone(),
+                });
             }
-            println!("Using active MUV environment: {}", active_muv_name);
-            return Ok((active_env_path, active_muv_name));
-        } else {
-            if env_name_arg.is_none() {
-                return Err(MuvError::Anyhow(anyhow::anyhow!(
-                    "A virtual environment is active (VIRTUAL_ENV={}), but it does not appear to be a MUV-managed environment or MUV_ENV_NAME is not set/inconsistent.\nPlease specify a MUV environment name or activate a MUV environment.",
-                    active_env_path.display()
-                )));
+            // Commands look the environment up again by name, so one shadowed by an
+            // environment in an earlier root can't be used: they'd act on the other.
+            if let Some((found, _)) = roots::find_env(roots, &active_muv_name)
+                && !is_same_dir(&found, &active_env_path)
+            {
+                return Err(MuvError::ActiveEnvShadowed {
+                    name: active_muv_name,
+                    active: active_env_path,
+                    shadowing: found,
+                });
             }
+            let source = if env_name_arg.is_some() {
+                EnvSource::Argument
+            } else {
+                EnvSource::Active
+            };
+            return Ok(ResolvedEnv {
+                path: active_env_path,
+                name: active_muv_name,
+                source,
+            });
+        } else if env_name_arg.is_none() {
+            return Err(MuvError::ForeignEnvActive(active_env_path));
         }
     }
 
     if let Some(name) = env_name_arg {
-        let env_path = ensure_env_exists(name)?;
-        return Ok((env_path, name.clone()));
+        let env_path = find(name)?;
+        return Ok(ResolvedEnv {
+            path: env_path,
+            name: name.clone(),
+            source: EnvSource::Argument,
+        });
     }
 
-    Err(MuvError::Anyhow(anyhow::anyhow!(
-        "No MUV environment is active, and no environment name was specified.\nUse 'muv activate <n>' or provide the environment name to the command."
-    )))
+    if let Some(project_file) = env::current_dir()
+        .ok()
+        .and_then(|cwd| find_project_env_file(&cwd))
+    {
+        let name = read_project_env_file(&project_file)?;
+        let env_path = find(&name)?;
+        return Ok(ResolvedEnv {
+            path: env_path,
+            name,
+            source: EnvSource::ProjectFile(project_file),
+        });
+    }
+
+    Err(MuvError::NoEnvironmentSelected(
+        PROJECT_ENV_FILE.to_string(),
+    ))
+}
+
+/// Whether `a` and `b` name the same directory, even if spelled differently.
+fn is_same_dir(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Like [`resolve_env`], returning just the path and name. Tells the user (on stderr,
+/// so command output stays clean) when the environment was picked implicitly.
+pub fn get_active_or_specified_env(env_name_arg: Option<&String>) -> Result<(PathBuf, String)> {
+    Ok(announce_resolved(resolve_env(env_name_arg)?))
+}
+
+/// The path and name of a resolved environment, telling the user (on stderr) when it
+/// was picked implicitly.
+pub fn announce_resolved(resolved: ResolvedEnv) -> (PathBuf, String) {
+    match &resolved.source {
+        EnvSource::Argument => {}
+        EnvSource::Active => eprintln!("Using active MUV environment: {}", resolved.name),
+        EnvSource::ProjectFile(file) => eprintln!(
+            "Using MUV environment '{}' from {}",
+            resolved.name,
+            file.display()
+        ),
+    }
+    (resolved.path, resolved.name)
 }
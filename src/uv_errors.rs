@@ -0,0 +1,126 @@
+use crate::error::MuvError;
+
+/// Turn a failed uv invocation into the most specific [`MuvError`] its stderr allows,
+/// falling back to [`MuvError::UvCommandFailed`] with `fallback` as the message.
+pub fn classify(stderr: &str, fallback: String) -> MuvError {
+    let stderr = strip_ansi(stderr);
+    let lines: Vec<&str> = stderr.lines().map(clean_line).collect();
+
+    if let Some(package) = lines.iter().find_map(|line| failed_build_package(line)) {
+        return MuvError::BuildFailed {
+            package,
+            detail: build_failure_cause(&lines),
+        };
+    }
+    if let Some(request) = lines.iter().find_map(|line| missing_interpreter(line)) {
+        return MuvError::InterpreterNotFound(request);
+    }
+    if let Some(start) = lines
+        .iter()
+        .position(|line| line.contains("No solution found when resolving"))
+    {
+        let explanation: Vec<&str> = lines[start + 1..]
+            .iter()
+            .copied()
+            .take_while(|line| !line.is_empty() && !line.starts_with("hint:"))
+            .collect();
+        let explanation = if explanation.is_empty() {
+            lines[start].to_string()
+        } else {
+            explanation.join(" ")
+        };
+        return MuvError::ResolutionFailed(explanation);
+    }
+    if let Some(line) = lines.iter().find(|line| is_network_error(line)) {
+        return MuvError::NetworkError(line.to_string());
+    }
+    MuvError::UvCommandFailed(fallback)
+}
+
+const NETWORK_MARKERS: &[&str] = &[
+    "Network connectivity is disabled",
+    "error sending request",
+    "dns error",
+    "failed to lookup address",
+    "Connection refused",
+    "Connection reset",
+    "operation timed out",
+    "Could not connect",
+    "client error (Connect)",
+];
+
+fn is_network_error(line: &str) -> bool {
+    NETWORK_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// `Failed to build `pkg==1.0`` / `Failed to download and build `pkg``: the package.
+fn failed_build_package(line: &str) -> Option<String> {
+    let rest = line
+        .strip_prefix("Failed to build")
+        .or_else(|| line.strip_prefix("Failed to download and build"))?;
+    let package = rest.split('`').nth(1)?;
+    // `name @ file:///...` or `name==1.0`: just the name and version.
+    let package = package.split(" @ ").next().unwrap_or(package);
+    Some(package.to_string())
+}
+
+/// The most useful line explaining why a build failed, if uv's output has one.
+fn build_failure_cause(lines: &[&str]) -> Option<String> {
+    const CAUSES: &[&str] = &[
+        "Python.h: No such file",
+        "ModuleNotFoundError",
+        "No module named",
+        "command 'gcc' failed",
+        "command 'cc' failed",
+        "error: can't find Rust compiler",
+        "pkg-config",
+        "fatal error:",
+    ];
+    lines
+        .iter()
+        .find(|line| CAUSES.iter().any(|cause| line.contains(cause)))
+        .map(|line| line.to_string())
+}
+
+/// `No interpreter found for Python 3.13 in ...` / `No download found for request:
+/// cpython-3.99-...`: the requested version.
+fn missing_interpreter(line: &str) -> Option<String> {
+    if let Some(rest) = line.split("No interpreter found for ").nth(1) {
+        let request = rest.split(" in ").next().unwrap_or(rest);
+        return Some(request.trim_start_matches("Python ").to_string());
+    }
+    if let Some(rest) = line.split("No download found for request: ").nth(1) {
+        return Some(rest.trim().to_string());
+    }
+    line.contains("No Python interpreters found")
+        .then(|| "python".to_string())
+}
+
+/// Drop uv's diagnostic decorations (`×`, `╰─▶`, `│`) and surrounding whitespace.
+fn clean_line(line: &str) -> &str {
+    line.trim()
+        .trim_start_matches(['×', '╰', '─', '▶', '│', '├'])
+        .trim()
+        .trim_start_matches("error:")
+        .trim()
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences: ESC [ ... final byte in '@'..='~'.
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use clap::Parser;
+use common::fake_backend::FakeBackend;
+use muv::backend::EnvBackend;
+use muv::cli::{Cli, Commands};
+use muv::commands;
+use muv::staging::STAGING_DIR;
+use muv::{Muv, history};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+// A client for its own muv home over the shared fake backend, so tests can inspect
+// the backend's calls afterwards. Handlers look environments up through the client,
+// so nothing depends on MUV_HOME.
+fn client(home: &Path, backend: &Arc<FakeBackend>) -> Muv {
+    Muv::new(home, backend.clone(), Box::new(io::sink()))
+}
+
+fn parse(args: &[&str]) -> Commands {
+    Cli::try_parse_from(std::iter::once("muv").chain(args.iter().copied()))
+        .expect("invalid test command line")
+        .command
+}
+
+#[test]
+fn test_handlers_drive_backend() {
+    let temp_dir = common::setup_test_env();
+    let home = temp_dir.path();
+    let backend = Arc::new(FakeBackend::new());
+
+    let Commands::Create(args) = parse(&["create", "app", "--python", "3.11", "click==8.1.7"])
+    else {
+        unreachable!()
+    };
+    commands::create::handle_create(args, &client(home, &backend)).unwrap();
+    let env_path = client(home, &backend).env("app").unwrap().path;
+
+    let Commands::Install(args) = parse(&["install", "-e", "app", "requests==2.31.0", "rich"])
+    else {
+        unreachable!()
+    };
+    commands::install::handle_install(args, &client(home, &backend)).unwrap();
+    assert_eq!(
+        backend.freeze(&env_path).unwrap(),
+        "click==8.1.7\nrequests==2.31.0\nrich==1.0.0\n"
+    );
+
+    let Commands::Uninstall(args) = parse(&["uninstall", "-e", "app", "rich"]) else {
+        unreachable!()
+    };
+    commands::uninstall::handle_uninstall(args, &client(home, &backend)).unwrap();
+
+    let Commands::Rollback(args) = parse(&["rollback", "app"]) else {
+        unreachable!()
+    };
+    commands::rollback::handle_rollback(args, &client(home, &backend)).unwrap();
+    assert_eq!(
+        backend.freeze(&env_path).unwrap(),
+        "click==8.1.7\nrequests==2.31.0\nrich==1.0.0\n"
+    );
+
+    let calls = backend.calls();
+    // The environment was built in the staging directory, then moved into place.
+    let staging = env_path.parent().unwrap().join(STAGING_DIR);
+    assert!(calls[0].starts_with(&format!("create_venv {}/", staging.display())));
+    assert!(calls[0].ends_with("/app 3.11"));
+    assert_eq!(fs::read_dir(&staging).unwrap().count(), 0);
+    assert_eq!(
+        calls.last().unwrap(),
+        &format!("sync {}", env_path.display())
+    );
+    // create, install, uninstall and rollback are all in the history.
+    assert_eq!(history::load(&env_path).unwrap().len(), 4);
+}
+
+#[test]
+fn test_upgrade_goes_through_backend() {
+    let temp_dir = common::setup_test_env();
+    let home = temp_dir.path();
+    let backend = Arc::new(FakeBackend::new());
+
+    let Commands::Create(args) = parse(&["create", "tools", "black"]) else {
+        unreachable!()
+    };
+    commands::create::handle_create(args, &client(home, &backend)).unwrap();
+    let Commands::Upgrade(args) = parse(&["upgrade", "tools", "--all"]) else {
+        unreachable!()
+    };
+    commands::upgrade::handle_upgrade(args, &client(home, &backend)).unwrap();
+
+    let env_path = client(home, &backend).env("tools").unwrap().path;
+    assert_eq!(backend.freeze(&env_path).unwrap(), "black==2.0.0\n");
+}
@@ -0,0 +1,69 @@
+use muv::cache::{cache_key, ensure_cached};
+use std::fs;
+use std::path::Path;
+use std::sync::Barrier;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+#[test]
+fn test_cached_env_is_built_once_and_partial_builds_are_redone() {
+    let temp_dir = TempDir::new().unwrap();
+    let builds = AtomicUsize::new(0);
+    let build = |path: &Path| {
+        let n = builds.fetch_add(1, Ordering::SeqCst) + 1;
+        fs::create_dir_all(path)?;
+        fs::write(path.join("built"), n.to_string())?;
+        Ok(())
+    };
+
+    // A build that happened in place and was interrupted left this behind.
+    let partial = temp_dir.path().join("env");
+    fs::create_dir_all(&partial).unwrap();
+    fs::write(partial.join("leftover"), "").unwrap();
+
+    let path = ensure_cached(temp_dir.path(), "env", build).unwrap();
+    assert_eq!(path, partial);
+    assert!(!path.join("leftover").exists());
+    ensure_cached(temp_dir.path(), "env", build).unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+    assert_eq!(fs::read_to_string(path.join("built")).unwrap(), "1");
+}
+
+#[test]
+fn test_concurrent_builds_of_the_same_env() {
+    let temp_dir = TempDir::new().unwrap();
+    let both_building = Barrier::new(2);
+    let build = |path: &Path| {
+        fs::create_dir_all(path)?;
+        fs::write(path.join("first-half"), "")?;
+        // Neither finishes before the other has started.
+        both_building.wait();
+        fs::write(path.join("second-half"), "")?;
+        Ok(())
+    };
+
+    let paths: Vec<_> = std::thread::scope(|scope| {
+        let builders: Vec<_> = (0..2)
+            .map(|_| scope.spawn(|| ensure_cached(temp_dir.path(), "env", build)))
+            .collect();
+        builders
+            .into_iter()
+            .map(|builder| builder.join().unwrap().unwrap())
+            .collect()
+    });
+
+    let path = temp_dir.path().join("env");
+    assert_eq!(paths, [path.clone(), path.clone()]);
+    assert!(path.join("first-half").exists());
+    assert!(path.join("second-half").exists());
+    // The losing build was thrown away.
+    let staging = temp_dir.path().join(muv::staging::STAGING_DIR);
+    assert_eq!(fs::read_dir(staging).unwrap().count(), 0);
+}
+
+#[test]
+fn test_cache_key_depends_on_every_part() {
+    assert_eq!(cache_key(["a", "b"]), cache_key(["a", "b"]));
+    assert_ne!(cache_key(["a", "b"]), cache_key(["b", "a"]));
+    assert_eq!(cache_key(["a"]).len(), 16);
+}
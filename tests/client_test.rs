@@ -0,0 +1,159 @@
+mod common;
+
+use common::fake_backend::FakeBackend;
+use muv::backend::InstallRequest;
+use muv::requirements::Requirement;
+use muv::{EnvSpec, Muv, MuvError};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+// An output sink the test can read back.
+#[derive(Clone, Default)]
+struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl SharedOutput {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_client_create_install_freeze() {
+    let temp_dir = common::setup_test_env();
+    let output = SharedOutput::default();
+    let muv = Muv::new(
+        temp_dir.path(),
+        Arc::new(FakeBackend::new()),
+        Box::new(output.clone()),
+    );
+
+    let env = muv
+        .create(&EnvSpec {
+            name: "app".to_string(),
+            python: Some("3.11".to_string()),
+            packages: vec!["click==8.1.7".to_string()],
+            root: None,
+            keep_on_failure: false,
+        })
+        .unwrap();
+    assert_eq!(env.name, "app");
+    assert_eq!(env.path, temp_dir.path().join("envs").join("app"));
+    assert!(output.text().contains("Creating environment 'app'"));
+
+    muv.install(
+        "app",
+        &InstallRequest::packages(["rich==13.0.0".to_string()]),
+    )
+    .unwrap();
+    assert_eq!(
+        muv.freeze("app").unwrap(),
+        vec![
+            Requirement {
+                name: "click".to_string(),
+                specifier: "==8.1.7".to_string(),
+            },
+            Requirement {
+                name: "rich".to_string(),
+                specifier: "==13.0.0".to_string(),
+            },
+        ]
+    );
+
+    let names: Vec<String> = muv
+        .list_envs()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    assert_eq!(names, vec!["app"]);
+}
+
+#[test]
+fn test_client_errors_are_typed() {
+    let temp_dir = common::setup_test_env();
+    let muv = Muv::new(
+        temp_dir.path(),
+        Arc::new(FakeBackend::new()),
+        Box::new(io::sink()),
+    );
+
+    assert!(matches!(
+        muv.freeze("missing"),
+        Err(MuvError::EnvironmentNotFound(_))
+    ));
+    let spec = EnvSpec {
+        name: "app".to_string(),
+        ..EnvSpec::default()
+    };
+    muv.create(&spec).unwrap();
+    assert!(matches!(
+        muv.create(&spec),
+        Err(MuvError::EnvironmentAlreadyExists(_))
+    ));
+}
+
+#[test]
+fn test_client_manages_packages_and_environments() {
+    let temp_dir = common::setup_test_env();
+    let muv = Muv::new(
+        temp_dir.path(),
+        Arc::new(FakeBackend::new()),
+        Box::new(io::sink()),
+    );
+    let spec = EnvSpec {
+        name: "app".to_string(),
+        packages: vec!["click==1.0.0".to_string(), "rich==1.0.0".to_string()],
+        ..EnvSpec::default()
+    };
+    muv.create(&spec).unwrap();
+
+    let diff = muv
+        .upgrade("app", Some(&["click".to_string()]), &[])
+        .unwrap();
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].to, "2.0.0");
+    muv.uninstall("app", &["rich".to_string()], &[]).unwrap();
+    assert_eq!(muv.history("app").unwrap().len(), 3);
+
+    // Undo the uninstall.
+    let diff = muv.rollback("app", None).unwrap();
+    assert_eq!(diff.added[0].name, "rich");
+    assert_eq!(muv.freeze("app").unwrap().len(), 2);
+
+    muv.set_tags("app", &["web".to_string()]).unwrap();
+    assert_eq!(muv.env("app").unwrap().tags, vec!["web"]);
+
+    let entry = muv.delete("app", false).unwrap().unwrap();
+    assert_eq!(entry.info.name, "app");
+    assert!(matches!(
+        muv.env("app"),
+        Err(MuvError::EnvironmentNotFound(_))
+    ));
+    assert_eq!(muv.restore("app").unwrap().tags, vec!["web"]);
+    assert!(matches!(muv.restore("app"), Err(MuvError::NotInTrash(_))));
+    assert_eq!(muv.empty_trash(None).unwrap(), 0);
+}
+
+#[test]
+fn test_list_json() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    common::create_fake_env(temp_dir.path(), "app", "");
+
+    let output = common::run_muv_command(&["list", "--json"], &[("MUV_HOME", muv_home)]);
+    assert!(output.status.success());
+    let envs: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envs[0]["name"], "app");
+    assert_eq!(envs[0]["tags"], serde_json::json!([]));
+}
@@ -0,0 +1,163 @@
+// An in-memory `EnvBackend` for driving command handlers without uv. Environments get
+// a real skeleton on disk (handlers look for `pyvenv.cfg`), but their packages only
+// live in memory, keyed by an id file in the skeleton so they follow the environment
+// when it's moved.
+
+use muv::backend::{EnvBackend, InstallRequest};
+use muv::requirements::{OutdatedPackage, parse_requirement};
+use muv::{MuvError, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+// Normalized package name -> (name, version).
+type Packages = BTreeMap<String, (String, String)>;
+
+const ID_FILE: &str = ".fake-backend-id";
+
+#[derive(Default)]
+pub struct FakeBackend {
+    envs: Mutex<HashMap<String, Packages>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One line per backend call, e.g. `install /path/to/env requests`.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn log(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn with_env<T>(&self, env_path: &Path, f: impl FnOnce(&mut Packages) -> T) -> Result<T> {
+        let id = fs::read_to_string(env_path.join(ID_FILE)).unwrap_or_default();
+        let mut envs = self.envs.lock().unwrap();
+        let packages = envs.get_mut(&id).ok_or_else(|| {
+            MuvError::UvCommandFailed(format!("no venv at {}", env_path.display()))
+        })?;
+        Ok(f(packages))
+    }
+}
+
+fn requirement_lines(files: &[String]) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    for file in files {
+        lines.extend(fs::read_to_string(file)?.lines().map(str::to_string));
+    }
+    Ok(lines)
+}
+
+impl EnvBackend for FakeBackend {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn create_venv(&self, env_path: &Path, python: Option<&str>) -> Result<()> {
+        self.log(format!(
+            "create_venv {} {}",
+            env_path.display(),
+            python.unwrap_or("-")
+        ));
+        fs::create_dir_all(env_path.join("bin"))?;
+        fs::create_dir_all(env_path.join("lib/python3.12/site-packages"))?;
+        fs::write(
+            env_path.join("pyvenv.cfg"),
+            format!(
+                "home = /usr/bin\nversion_info = {}\n",
+                python.unwrap_or("3.12.0")
+            ),
+        )?;
+        let mut envs = self.envs.lock().unwrap();
+        let id = envs.len().to_string();
+        fs::write(env_path.join(ID_FILE), &id)?;
+        envs.insert(id, BTreeMap::new());
+        Ok(())
+    }
+
+    fn install(&self, env_path: &Path, request: &InstallRequest) -> Result<()> {
+        self.log(format!(
+            "install {} {}",
+            env_path.display(),
+            request.packages.join(" ")
+        ));
+        let mut specs = requirement_lines(&request.requirements)?;
+        specs.extend(request.packages.iter().cloned());
+        let default_version = if request.upgrade { "2.0.0" } else { "1.0.0" };
+        self.with_env(env_path, |packages| {
+            for requirement in specs.iter().filter_map(|s| parse_requirement(s)) {
+                let version = requirement
+                    .pinned_version()
+                    .unwrap_or(default_version)
+                    .to_string();
+                packages.insert(
+                    requirement.normalized_name(),
+                    (requirement.name.clone(), version),
+                );
+            }
+        })
+    }
+
+    fn uninstall(
+        &self,
+        env_path: &Path,
+        packages: &[String],
+        requirements: &[String],
+    ) -> Result<()> {
+        self.log(format!(
+            "uninstall {} {}",
+            env_path.display(),
+            packages.join(" ")
+        ));
+        let mut specs = requirement_lines(requirements)?;
+        specs.extend(packages.iter().cloned());
+        self.with_env(env_path, |installed| {
+            for requirement in specs.iter().filter_map(|s| parse_requirement(s)) {
+                installed.remove(&requirement.normalized_name());
+            }
+        })
+    }
+
+    fn freeze(&self, env_path: &Path) -> Result<String> {
+        self.with_env(env_path, |packages| {
+            packages
+                .values()
+                .map(|(name, version)| format!("{}=={}\n", name, version))
+                .collect()
+        })
+    }
+
+    fn list_outdated(
+        &self,
+        env_path: &Path,
+        _index_args: &[String],
+    ) -> Result<Vec<OutdatedPackage>> {
+        self.with_env(env_path, |_| Vec::new())
+    }
+
+    fn compile(&self, input: &Path, output: &Path, _index_args: &[String]) -> Result<()> {
+        fs::copy(input, output)?;
+        Ok(())
+    }
+
+    fn sync(&self, env_path: &Path, requirements: &Path) -> Result<()> {
+        self.log(format!("sync {}", env_path.display()));
+        let content = fs::read_to_string(requirements)?;
+        self.with_env(env_path, |packages| {
+            packages.clear();
+            for requirement in content.lines().filter_map(parse_requirement) {
+                let version = requirement.pinned_version().unwrap_or("1.0.0").to_string();
+                packages.insert(
+                    requirement.normalized_name(),
+                    (requirement.name.clone(), version),
+                );
+            }
+        })
+    }
+}
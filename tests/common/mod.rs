@@ -1,16 +1,42 @@
-use std::path::PathBuf;
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
 
+pub mod fake_backend;
+
 // Helper function to create a temporary directory for tests
 pub fn setup_test_env() -> TempDir {
     tempfile::tempdir().expect("Failed to create temp directory")
 }
 
+// Tests never read the developer's own config file unless they set MUV_CONFIG.
+const NO_CONFIG: &str = "/nonexistent/muv/config.toml";
+
 // Helper to run the muv binary with arguments
 pub fn run_muv_command(args: &[&str], env_vars: &[(&str, &str)]) -> std::process::Output {
     let mut cmd = Command::new(env!("CARGO_BIN_EXE_muv"));
-    cmd.args(args);
+    cmd.args(args).env("MUV_CONFIG", NO_CONFIG);
+
+    for (key, val) in env_vars {
+        cmd.env(key, val);
+    }
+
+    cmd.output().expect("Failed to execute muv command")
+}
+
+// Helper to run the muv binary from a specific working directory
+pub fn run_muv_command_in(
+    dir: &Path,
+    args: &[&str],
+    env_vars: &[(&str, &str)],
+) -> std::process::Output {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_muv"));
+    cmd.args(args).current_dir(dir).env("MUV_CONFIG", NO_CONFIG);
 
     for (key, val) in env_vars {
         cmd.env(key, val);
@@ -23,3 +49,71 @@ pub fn run_muv_command(args: &[&str], env_vars: &[(&str, &str)]) -> std::process
 pub fn temp_path(temp_dir: &TempDir, path: &str) -> PathBuf {
     temp_dir.path().join(path)
 }
+
+// Helper to create a directory that looks like a muv environment to the CLI.
+// `freeze` is what the fake uv reports as installed in it.
+pub fn create_fake_env(muv_home: &Path, name: &str, freeze: &str) -> PathBuf {
+    let env_path = muv_home.join("envs").join(name);
+    fs::create_dir_all(env_path.join("bin")).expect("Failed to create fake env");
+    fs::create_dir_all(env_path.join("lib/python3.12/site-packages"))
+        .expect("Failed to create fake site-packages");
+    fs::write(env_path.join("pyvenv.cfg"), "home = /usr/bin\n")
+        .expect("Failed to write pyvenv.cfg");
+    fs::write(env_path.join(".fake-freeze"), freeze).expect("Failed to write fake freeze");
+    env_path
+}
+
+// Helper to install a fake `uv` into `dir`. It answers `--version`, `venv` (creating
+// a skeleton environment whose `python` runs its script with `sh`), `pip freeze` (from the env's `.fake-freeze` file) and
+// `pip list` (from `.fake-outdated`), and logs every invocation to `dir/uv.log`. `pip install` replaces `.fake-freeze` with
+// `.fake-after-install` when the latter exists, and `pip sync` copies its file there.
+pub fn install_fake_uv(dir: &Path) -> PathBuf {
+    let bin_dir = dir.join("fake-bin");
+    fs::create_dir_all(&bin_dir).expect("Failed to create fake bin directory");
+    let script = format!(
+        r#"#!/bin/sh
+echo "$@" >> "{log}"
+case "$1 $2" in
+    "--version "*) echo "uv 0.0.0-fake" ;;
+    "venv "*)
+        for target in "$@"; do :; done
+        mkdir -p "$target/bin" "$target/lib/python3.12/site-packages"
+        echo "home = /usr/bin" > "$target/pyvenv.cfg"
+        printf '#!/bin/sh\nexec sh "$@"\n' > "$target/bin/python"
+        chmod +x "$target/bin/python" ;;
+    "pip freeze") cat "$VIRTUAL_ENV/.fake-freeze" 2>/dev/null ;;
+    "pip list") cat "$VIRTUAL_ENV/.fake-outdated" 2>/dev/null || echo "[]" ;;
+    "pip sync") cp "$3" "$VIRTUAL_ENV/.fake-freeze" ;;
+    "pip install")
+        if [ -f "$VIRTUAL_ENV/.fake-after-install" ]; then
+            mv "$VIRTUAL_ENV/.fake-after-install" "$VIRTUAL_ENV/.fake-freeze"
+        fi ;;
+    *) exit 0 ;;
+esac
+"#,
+        log = dir.join("uv.log").display()
+    );
+    let uv_path = bin_dir.join("uv");
+    fs::write(&uv_path, script).expect("Failed to write fake uv");
+    fs::set_permissions(&uv_path, fs::Permissions::from_mode(0o755))
+        .expect("Failed to make fake uv executable");
+    bin_dir
+}
+
+// PATH value that puts the fake uv first.
+pub fn path_with(bin_dir: &Path) -> String {
+    format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    )
+}
+
+// Helper to put an executable shell script into a fake env's `bin` directory.
+pub fn write_env_script(env_path: &Path, name: &str, body: &str) -> PathBuf {
+    let script_path = env_path.join("bin").join(name);
+    fs::write(&script_path, format!("#!/bin/sh\n{}\n", body)).expect("Failed to write script");
+    fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))
+        .expect("Failed to make script executable");
+    script_path
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+// MUV_HOME, MUV_CONFIG (inside the temp dir) and a PATH with the fake uv.
+fn test_env(temp_dir: &TempDir) -> Vec<(&'static str, String)> {
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    vec![
+        ("MUV_HOME", temp_dir.path().display().to_string()),
+        (
+            "MUV_CONFIG",
+            temp_dir.path().join("config.toml").display().to_string(),
+        ),
+        ("PATH", common::path_with(&fake_bin)),
+    ]
+}
+
+fn run(args: &[&str], env: &[(&'static str, String)]) -> std::process::Output {
+    let env: Vec<(&str, &str)> = env.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    common::run_muv_command(args, &env)
+}
+
+fn stdout(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn uv_log(dir: &Path) -> String {
+    fs::read_to_string(dir.join("uv.log")).unwrap_or_default()
+}
+
+#[test]
+fn test_config_set_get_list_unset() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+
+    assert!(
+        run(&["config", "set", "python", "3.11"], &env)
+            .status
+            .success()
+    );
+    assert!(
+        run(
+            &["config", "set", "default-packages", "ipython, rich"],
+            &env
+        )
+        .status
+        .success()
+    );
+    assert!(
+        run(&["config", "set", "confirm-delete", "false"], &env)
+            .status
+            .success()
+    );
+
+    let content = fs::read_to_string(temp_dir.path().join("config.toml")).unwrap();
+    assert!(content.contains("python = \"3.11\""));
+    assert!(content.contains("confirm-delete = false"));
+
+    assert_eq!(
+        stdout(&run(&["config", "get", "default-packages"], &env)),
+        "ipython,rich\n"
+    );
+    let list = stdout(&run(&["config", "list"], &env));
+    assert!(list.contains("python = 3.11\n"));
+    assert!(list.contains("backend (not set)\n"));
+
+    assert!(run(&["config", "unset", "python"], &env).status.success());
+    assert_eq!(stdout(&run(&["config", "get", "python"], &env)), "");
+}
+
+#[test]
+fn test_config_rejects_unknown_keys_and_bad_values() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+
+    let output = run(&["config", "set", "colour", "blue"], &env);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown config key 'colour'"));
+
+    let output = run(&["config", "set", "backend", "conda"], &env);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected uv or pip"));
+    assert!(!temp_dir.path().join("config.toml").exists());
+}
+
+#[test]
+fn test_create_uses_configured_python_packages_and_index() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        "python = \"3.11\"\ndefault-packages = [\"ipython\"]\nindex-url = \"https://pypi.example/simple\"\n",
+    )
+    .unwrap();
+
+    let output = run(&["create", "app", "click"], &env);
+    assert!(output.status.success());
+    let env_path = temp_dir.path().join("envs").join("app");
+    let log = uv_log(temp_dir.path());
+    assert!(log.contains(&format!(
+        "venv --python 3.11 {}/",
+        env_path.parent().unwrap().display()
+    )));
+    assert!(env_path.join("pyvenv.cfg").exists());
+    assert!(log.contains("pip install click ipython --index-url https://pypi.example/simple"));
+
+    // A flag wins over the config file.
+    let output = run(&["create", "other", "--python", "3.12", "click"], &env);
+    assert!(output.status.success());
+    assert!(uv_log(temp_dir.path()).contains("venv --python 3.12 "));
+}
+
+#[test]
+fn test_envs_dir_from_config_and_environment() {
+    let temp_dir = common::setup_test_env();
+    let mut env = test_env(&temp_dir);
+    let configured = temp_dir.path().join("configured");
+    let overridden = temp_dir.path().join("overridden");
+    fs::create_dir_all(configured.join("one")).unwrap();
+    fs::write(configured.join("one").join("pyvenv.cfg"), "").unwrap();
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        format!("envs-dir = \"{}\"\n", configured.display()),
+    )
+    .unwrap();
+
+    let list = stdout(&run(&["list"], &env));
+    assert!(list.contains("- one"), "{}", list);
+
+    env.push(("MUV_ENVS_DIR", overridden.display().to_string()));
+    let list = stdout(&run(&["list"], &env));
+    assert!(!list.contains("- one"), "{}", list);
+}
+
+#[test]
+fn test_configured_output_confirmation_prompt_and_backend() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+    common::create_fake_env(temp_dir.path(), "app", "");
+    fs::write(
+        temp_dir.path().join("config.toml"),
+        "output = \"json\"\nprompt = \"[{name}] \"\nconfirm-delete = false\nbackend = \"pip\"\n",
+    )
+    .unwrap();
+
+    // Errors come out as JSON without --json.
+    let output = run(&["path", "missing"], &env);
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(report["error"]["kind"], "env_not_found");
+
+    let activate = stdout(&run(&["activate", "app"], &env));
+    assert!(activate.contains("PS1='[app] '\"$PS1\""), "{}", activate);
+
+    // The configured backend applies unless --backend says otherwise.
+    let output = run(
+        &["create", "viapip", "--python", "/nonexistent/python", "x"],
+        &env,
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("interpreter_not_found"));
+    let output = run(&["--backend", "uv", "create", "viauv", "click"], &env);
+    assert!(stdout(&output).contains("(using uv)"));
+
+    // No confirmation prompt (stdin is empty, which would otherwise mean "no").
+    let output = run(&["delete", "app"], &env);
+    assert!(output.status.success());
+    assert!(!temp_dir.path().join("envs").join("app").exists());
+}
+
+#[test]
+fn test_unknown_config_keys_are_ignored_with_a_warning() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "pyton = \"3.11\"\nbogus = 1\n").unwrap();
+
+    let output = run(&["list"], &env);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Warning: ignoring unknown key 'pyton'"),
+        "{}",
+        stderr
+    );
+    assert_eq!(stderr.matches("'bogus'").count(), 1, "{}", stderr);
+
+    // Setting a known key keeps the unknown ones; they can be unset by name.
+    assert!(
+        run(&["config", "set", "python", "3.11"], &env)
+            .status
+            .success()
+    );
+    assert!(run(&["config", "unset", "pyton"], &env).status.success());
+    let content = fs::read_to_string(&config_path).unwrap();
+    assert!(content.contains("python = \"3.11\""), "{}", content);
+    assert!(content.contains("bogus = 1"), "{}", content);
+    assert!(!content.contains("pyton"), "{}", content);
+}
+
+#[test]
+fn test_broken_config_can_still_be_fixed() {
+    let temp_dir = common::setup_test_env();
+    let env = test_env(&temp_dir);
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "output = 5\nprompt = \"> \"\n").unwrap();
+
+    let output = run(&["list"], &env);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("config.toml"));
+
+    assert!(run(&["config", "path"], &env).status.success());
+    let output = run(&["config", "set", "output", "text"], &env);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(run(&["list"], &env).status.success());
+    assert_eq!(stdout(&run(&["config", "get", "prompt"], &env)), "> \n");
+
+    fs::write(&config_path, "confirm-delete = \"maybe\"\n").unwrap();
+    assert!(
+        run(&["config", "unset", "confirm-delete"], &env)
+            .status
+            .success()
+    );
+    assert!(run(&["list"], &env).status.success());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_diff_between_environments() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "a", "flask==2.0.0\nsix==1.16.0\n");
+    common::create_fake_env(&muv_home, "b", "Flask==3.0.0\nrich==13.7.0\n");
+
+    let output = common::run_muv_command(
+        &["diff", "a", "b"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("+ rich 13.7.0"));
+    assert!(stdout.contains("- six 1.16.0"));
+    assert!(stdout.contains("~ flask 2.0.0 -> 3.0.0"));
+}
+
+#[test]
+fn test_diff_against_requirements_json() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "a", "requests==2.31.0\n");
+    let req_file = temp_dir.path().join("requirements.txt");
+    fs::write(&req_file, "requests==2.31.0\n").unwrap();
+
+    let output = common::run_muv_command(
+        &["diff", "a", "-r", req_file.to_str().unwrap(), "--json"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(report["from"], "a");
+    assert!(report["added"].as_array().unwrap().is_empty());
+    assert!(report["removed"].as_array().unwrap().is_empty());
+    assert!(report["changed"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use muv::env_vars;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_env_vars_apply_to_run() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "ml", "");
+    fs::write(
+        env_path.join(".env"),
+        "# defaults\nexport DJANGO_SETTINGS_MODULE=\"app.settings\"\nCUDA_VISIBLE_DEVICES=1\n",
+    )
+    .unwrap();
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    let output = common::run_muv_command(
+        &[
+            "env",
+            "set",
+            "ml",
+            "CUDA_VISIBLE_DEVICES=0",
+            "GREETING=it's here",
+        ],
+        &env_vars,
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = common::run_muv_command(&["env", "list", "ml"], &env_vars);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "DJANGO_SETTINGS_MODULE=app.settings\nCUDA_VISIBLE_DEVICES=0\nGREETING=it's here\n"
+    );
+
+    let output = common::run_muv_command(
+        &[
+            "run",
+            "ml",
+            "--env",
+            "GREETING=overridden",
+            "--",
+            "sh",
+            "-c",
+            "echo $CUDA_VISIBLE_DEVICES $DJANGO_SETTINGS_MODULE $GREETING",
+        ],
+        &env_vars,
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "0 app.settings overridden"
+    );
+
+    let output = common::run_muv_command(&["env", "unset", "ml", "GREETING"], &env_vars);
+    assert!(output.status.success());
+    let output = common::run_muv_command(&["env", "list", "ml"], &env_vars);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("GREETING"));
+
+    let output = common::run_muv_command(&["env", "set", "ml", "1BAD=x"], &env_vars);
+    assert!(!output.status.success());
+
+    // One bad key and nothing is set.
+    let output = common::run_muv_command(&["env", "set", "ml", "GOOD=1", "BAD-KEY=2"], &env_vars);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    let output = common::run_muv_command(&["env", "list", "ml"], &env_vars);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("GOOD"));
+}
+
+#[test]
+fn test_activate_exports_and_deactivate_restores_vars() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "web", "");
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+    let output =
+        common::run_muv_command(&["env", "set", "web", "DEBUG=1", "NEW_VAR=a b"], &env_vars);
+    assert!(output.status.success());
+
+    let script = r#"
+        export DEBUG=0
+        eval "$("$MUV_BIN" activate web)"
+        echo "active: $DEBUG $NEW_VAR"
+        deactivate 2>/dev/null
+        echo "restored: $DEBUG ${NEW_VAR-unset}"
+        eval "$("$MUV_BIN" activate web)"
+        eval "$("$MUV_BIN" deactivate)"
+        echo "again: $DEBUG ${NEW_VAR-unset}"
+    "#;
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env("MUV_BIN", env!("CARGO_BIN_EXE_muv"))
+        .env("MUV_HOME", &muv_home)
+        .env("PATH", &path)
+        .env_remove("VIRTUAL_ENV")
+        .env_remove("MUV_ENV_NAME")
+        .output()
+        .expect("Failed to run bash");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("active: 1 a b"), "{}", stdout);
+    assert!(stdout.contains("restored: 0 unset"), "{}", stdout);
+    assert!(stdout.contains("again: 0 unset"), "{}", stdout);
+}
+
+#[test]
+fn test_deactivate_script_in_bash_and_zsh() {
+    let vars = [
+        ("DEBUG".to_string(), "1".to_string()),
+        ("NEW_VAR".to_string(), "a b".to_string()),
+    ];
+    let script = format!(
+        "export DEBUG=0\n{}echo \"active: $DEBUG $NEW_VAR\"\n{}echo \"restored: $DEBUG ${{NEW_VAR-unset}} ${{_MUV_ENV_VARS-unset}}\"\n",
+        env_vars::activate_script(&vars),
+        env_vars::deactivate_script(&["DEBUG", "NEW_VAR"]),
+    );
+    let mut ran = 0;
+    for shell in ["bash", "zsh"] {
+        // zsh isn't installed everywhere.
+        let Ok(output) = Command::new(shell).arg("-c").arg(&script).output() else {
+            continue;
+        };
+        ran += 1;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("active: 1 a b"), "{}: {}", shell, stdout);
+        assert!(
+            stdout.contains("restored: 0 unset unset"),
+            "{}: {}",
+            shell,
+            stdout
+        );
+    }
+    assert!(ran > 0);
+}
@@ -1,5 +1,8 @@
 mod common;
 
+use muv::error::{MuvError, as_muv_error};
+use std::fs;
+
 #[test]
 fn test_nonexistent_environment() {
     let temp_dir = common::setup_test_env();
@@ -34,3 +37,156 @@ fn test_path_nonexistent_env() {
 
     assert!(!output.status.success());
 }
+
+#[test]
+fn test_exit_codes_by_failure_kind() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    common::create_fake_env(temp_dir.path(), "app", "");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env = [
+        ("MUV_HOME", muv_home),
+        ("PATH", &common::path_with(&fake_bin)),
+    ];
+
+    let not_found = common::run_muv_command(&["freeze", "missing"], &env);
+    assert_eq!(not_found.status.code(), Some(3));
+
+    let exists = common::run_muv_command(&["create", "app", "click"], &env);
+    assert_eq!(exists.status.code(), Some(4));
+
+    let invalid = common::run_muv_command(&["create", "../escape", "click"], &env);
+    assert_eq!(invalid.status.code(), Some(5));
+    assert!(!temp_dir.path().join("escape").exists());
+
+    let usage = common::run_muv_command(&["create"], &env);
+    assert_eq!(usage.status.code(), Some(2));
+
+    let app_path = temp_dir.path().join("envs").join("app");
+    let mut active = env.to_vec();
+    active.extend([
+        ("VIRTUAL_ENV", app_path.to_str().unwrap()),
+        ("MUV_ENV_NAME", "app"),
+    ]);
+    let conflict = common::run_muv_command(&["freeze", "other"], &active);
+    assert_eq!(conflict.status.code(), Some(15));
+
+    let bad_key = common::run_muv_command(&["env", "set", "app", "BAD-KEY=1"], &env);
+    assert_eq!(bad_key.status.code(), Some(16));
+
+    let pyproject = temp_dir.path().join("pyproject.toml");
+    fs::write(
+        &pyproject,
+        "[project]\nname = \"demo\"\ndependencies = []\n",
+    )
+    .unwrap();
+    let unknown_extra = common::run_muv_command(
+        &[
+            "install",
+            "-e",
+            "app",
+            "--toml",
+            pyproject.to_str().unwrap(),
+            "--extra",
+            "missing",
+        ],
+        &env,
+    );
+    assert_eq!(unknown_extra.status.code(), Some(17));
+}
+
+#[test]
+fn test_typed_error_inside_anyhow_wrapper() {
+    let wrapped = anyhow::Error::from(MuvError::Anyhow(
+        anyhow::Error::from(MuvError::EnvironmentNotFound("app".to_string()))
+            .context("Looking up 'app'"),
+    ));
+    assert!(matches!(
+        as_muv_error(&wrapped),
+        Some(MuvError::EnvironmentNotFound(_))
+    ));
+
+    let untyped = anyhow::Error::from(MuvError::Anyhow(anyhow::anyhow!("something")));
+    assert!(matches!(as_muv_error(&untyped), Some(MuvError::Anyhow(_))));
+}
+
+#[test]
+fn test_existing_env_with_other_characters() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    // Made before names were restricted.
+    let env_path = common::create_fake_env(temp_dir.path(), "my env", "");
+
+    let output = common::run_muv_command(&["path", "my env"], &[("MUV_HOME", muv_home)]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        env_path.display().to_string()
+    );
+
+    let output = common::run_muv_command(&["delete", "my env", "--yes"], &[("MUV_HOME", muv_home)]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!env_path.exists());
+
+    let output = common::run_muv_command(&["restore", "my env"], &[("MUV_HOME", muv_home)]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(env_path.exists());
+
+    let escape = common::run_muv_command(&["path", "../my env"], &[("MUV_HOME", muv_home)]);
+    assert_eq!(escape.status.code(), Some(3));
+}
+
+#[test]
+fn test_error_hint() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+
+    let output = common::run_muv_command(&["path", "missing"], &[("MUV_HOME", muv_home)]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: Environment 'missing' not found."));
+    assert!(stderr.contains("Hint: Run 'muv list'"));
+}
+
+#[test]
+fn test_error_as_json() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+
+    let output = common::run_muv_command(&["path", "missing", "--json"], &[("MUV_HOME", muv_home)]);
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("stderr is not JSON");
+    assert_eq!(report["error"]["kind"], "env_not_found");
+    assert_eq!(report["error"]["code"], 3);
+    assert_eq!(
+        report["error"]["message"],
+        "Environment 'missing' not found."
+    );
+    assert!(report["error"]["hint"].is_string());
+}
+
+#[test]
+fn test_error_source_is_shown_once() {
+    let temp_dir = common::setup_test_env();
+    let config_path = temp_dir.path().join("config.toml");
+    std::fs::write(&config_path, "output = \n").unwrap();
+
+    let output = common::run_muv_command(
+        &["list"],
+        &[
+            ("MUV_HOME", temp_dir.path().to_str().unwrap()),
+            ("MUV_CONFIG", config_path.to_str().unwrap()),
+        ],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to parse"), "{}", stderr);
+    assert_eq!(stderr.matches("TOML parse error").count(), 1, "{}", stderr);
+}
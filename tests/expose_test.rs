@@ -0,0 +1,72 @@
+mod common;
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_expose_creates_shims_and_detects_collisions() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let fmt_env = common::create_fake_env(&muv_home, "fmt", "");
+    let other_env = common::create_fake_env(&muv_home, "other", "");
+    common::write_env_script(&fmt_env, "black", "echo \"black from fmt: $*\"");
+    common::write_env_script(&other_env, "black", "echo \"black from other\"");
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    let output = common::run_muv_command(&["expose", "fmt", "black"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let shim = muv_home.join("bin").join("black");
+    let output = Command::new(&shim).arg("--check").output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "black from fmt: --check"
+    );
+
+    let output = common::run_muv_command(&["expose", "other", "black"], &env_vars);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("already exposed from environment 'fmt'")
+    );
+
+    let output = common::run_muv_command(&["expose", "fmt", "isort"], &env_vars);
+    assert!(!output.status.success());
+
+    let output = common::run_muv_command(&["exposed"], &env_vars);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("black") && stdout.contains("fmt"),
+        "{}",
+        stdout
+    );
+
+    let output = common::run_muv_command(&["expose", "other", "black", "--force"], &env_vars);
+    assert!(output.status.success());
+    let output = Command::new(&shim).output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "black from other"
+    );
+
+    let output = common::run_muv_command(&["delete", "other", "--yes"], &env_vars);
+    assert!(output.status.success());
+    assert!(!shim.exists());
+    assert!(
+        !fs::read_to_string(muv_home.join("exposed.json"))
+            .unwrap()
+            .contains("black")
+    );
+
+    let output = common::run_muv_command(&["expose", "fmt", "black"], &env_vars);
+    assert!(output.status.success());
+    let output = common::run_muv_command(&["unexpose", "black"], &env_vars);
+    assert!(output.status.success());
+    assert!(!shim.exists());
+    let output = common::run_muv_command(&["unexpose", "black"], &env_vars);
+    assert!(!output.status.success());
+}
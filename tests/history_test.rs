@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_install_is_recorded_and_can_be_rolled_back() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "requests==2.28.0\n");
+    fs::write(
+        env_path.join(".fake-after-install"),
+        "idna==3.4\nrequests==2.31.0\n",
+    )
+    .unwrap();
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    let install = common::run_muv_command(&["install", "-e", "app", "requests"], &env_vars);
+    assert!(
+        install.status.success(),
+        "{}",
+        String::from_utf8_lossy(&install.stderr)
+    );
+
+    let history = common::run_muv_command(&["history", "app"], &env_vars);
+    assert!(history.status.success());
+    let stdout = String::from_utf8(history.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("muv install -e app requests"));
+    assert!(stdout.contains("+1 -0 ~1"));
+
+    let rollback = common::run_muv_command(&["rollback", "app"], &env_vars);
+    assert!(
+        rollback.status.success(),
+        "{}",
+        String::from_utf8_lossy(&rollback.stderr)
+    );
+    let stdout = String::from_utf8(rollback.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("~ requests 2.31.0 -> 2.28.0"));
+    assert!(stdout.contains("- idna 3.4"));
+
+    let freeze = fs::read_to_string(env_path.join(".fake-freeze")).unwrap();
+    assert_eq!(freeze.trim(), "requests==2.28.0");
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert!(log.contains("pip sync"));
+
+    // The rollback itself is recorded, so it can be undone too.
+    let history = common::run_muv_command(&["history", "app"], &env_vars);
+    let stdout = String::from_utf8(history.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("muv rollback app"));
+}
+
+#[test]
+fn test_rollback_without_history_fails() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "fresh", "");
+
+    let output = common::run_muv_command(
+        &["rollback", "fresh", "--to", "3"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No history"));
+}
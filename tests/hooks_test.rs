@@ -0,0 +1,97 @@
+mod common;
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_hooks_run_around_activation() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "tools", "");
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    // A non-interactive "editor" that appends the hook body given in HOOK_BODY.
+    let editor = temp_dir.path().join("append-editor");
+    fs::write(&editor, "#!/bin/sh\necho \"$HOOK_BODY\" >> \"$1\"\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let editor = editor.to_str().unwrap();
+
+    for (hook, body) in [
+        (
+            "post-activate",
+            "export TOOLCHAIN=loaded; echo \"hello $MUV_ENV_NAME\"",
+        ),
+        (
+            "pre-deactivate",
+            "echo \"bye $MUV_ENV_NAME\"; unset TOOLCHAIN",
+        ),
+    ] {
+        let output = common::run_muv_command(
+            &["hooks", "edit", "tools", hook],
+            &[
+                env_vars[0],
+                env_vars[1],
+                ("VISUAL", editor),
+                ("HOOK_BODY", body),
+            ],
+        );
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let post_activate = env_path.join(".muv/hooks/post-activate");
+    assert!(
+        fs::read_to_string(&post_activate)
+            .unwrap()
+            .starts_with("# muv post-activate hook")
+    );
+
+    let output = common::run_muv_command(&["hooks", "list", "tools"], &env_vars);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("post-activate") && stdout.contains("pre-deactivate"));
+
+    let script = r#"
+        eval "$("$MUV_BIN" activate tools)"
+        echo "toolchain: $TOOLCHAIN"
+        deactivate 2>/dev/null
+        echo "after: ${TOOLCHAIN-unset}"
+        eval "$("$MUV_BIN" activate tools)"
+        eval "$("$MUV_BIN" deactivate)"
+    "#;
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env("MUV_BIN", env!("CARGO_BIN_EXE_muv"))
+        .env("MUV_HOME", &muv_home)
+        .env("PATH", &path)
+        .env_remove("VIRTUAL_ENV")
+        .env_remove("MUV_ENV_NAME")
+        .output()
+        .expect("Failed to run bash");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        [
+            "hello tools",
+            "toolchain: loaded",
+            "bye tools",
+            "after: unset",
+            "hello tools",
+            "bye tools"
+        ],
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = common::run_muv_command(&["hooks", "remove", "tools", "post-activate"], &env_vars);
+    assert!(output.status.success());
+    assert!(!post_activate.exists());
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_install_toml_with_extras_and_groups() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "app", "");
+    let project_dir = temp_dir.path().join("project");
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("pyproject.toml"),
+        r#"
+[project]
+name = "demo"
+dependencies = ["requests"]
+
+[project.optional-dependencies]
+cli = ["click"]
+
+[dependency-groups]
+test = ["pytest"]
+"#,
+    )
+    .unwrap();
+    let toml_path = project_dir.join("pyproject.toml");
+
+    let output = common::run_muv_command(
+        &[
+            "install",
+            "-e",
+            "app",
+            "-t",
+            toml_path.to_str().unwrap(),
+            "--extra",
+            "cli",
+            "--group",
+            "test",
+            "--editable",
+            project_dir.to_str().unwrap(),
+        ],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert!(log.contains(&format!(
+        "pip install -e {} requests click pytest",
+        project_dir.display()
+    )));
+}
+
+#[test]
+fn test_install_toml_rejects_incompatible_python() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "old", "");
+    fs::write(
+        env_path.join("pyvenv.cfg"),
+        "home = /usr/bin\nversion_info = 3.8.18\n",
+    )
+    .unwrap();
+    let toml_path = temp_dir.path().join("pyproject.toml");
+    fs::write(
+        &toml_path,
+        "[project]\nname = \"demo\"\nrequires-python = \">=3.10\"\ndependencies = [\"requests\"]\n",
+    )
+    .unwrap();
+
+    let output = common::run_muv_command(
+        &["install", "-e", "old", "-t", toml_path.to_str().unwrap()],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("3.8.18"));
+    assert!(stderr.contains(">=3.10"));
+
+    // uv is only looked for when first needed, so it may never have run at all.
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap_or_default();
+    assert!(!log.contains("pip install"));
+}
+
+#[test]
+fn test_install_resolves_everything_in_one_uv_call() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "mono", "");
+
+    let output = common::run_muv_command(
+        &[
+            "install",
+            "-e",
+            "mono",
+            "-r",
+            "a.txt",
+            "-r",
+            "b.txt",
+            "-c",
+            "constraints.txt",
+            "--override",
+            "overrides.txt",
+            "--editable",
+            "./libs/core",
+            "--no-deps",
+            "--index-url",
+            "https://pypi.example/simple",
+            "--find-links",
+            "./wheels",
+            "requests",
+        ],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    let installs: Vec<&str> = log
+        .lines()
+        .filter(|l| l.starts_with("pip install"))
+        .collect();
+    assert_eq!(
+        installs,
+        [
+            "pip install -r a.txt -r b.txt -e ./libs/core requests -c constraints.txt \
+          --override overrides.txt --no-deps --index-url https://pypi.example/simple \
+          --find-links ./wheels"
+        ]
+    );
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use muv::lock::{LockMode, lock_env};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_no_wait_fails_on_busy_env() {
+    let temp_dir = common::setup_test_env();
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(temp_dir.path(), "app", "click==8.1.7\n");
+    let path = common::path_with(&fake_bin);
+    let env = [
+        ("MUV_HOME", temp_dir.path().to_str().unwrap()),
+        ("PATH", path.as_str()),
+    ];
+
+    // Readers share the environment...
+    let reader = lock_env(temp_dir.path(), "app", LockMode::Shared, false).unwrap();
+    let output = common::run_muv_command(&["--no-wait", "freeze", "app"], &env);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "click==8.1.7\n");
+
+    let output = common::run_muv_command(&["--no-wait", "diff", "app", "app"], &env);
+    assert!(output.status.success());
+
+    // ...but nothing changes it while they use it.
+    let output = common::run_muv_command(
+        &["--no-wait", "--json", "install", "-e", "app", "rich"],
+        &env,
+    );
+    assert_eq!(output.status.code(), Some(14));
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(report["error"]["kind"], "env_busy");
+    let output = common::run_muv_command(&["--no-wait", "env", "set", "app", "A=1"], &env);
+    assert_eq!(output.status.code(), Some(14));
+    drop(reader);
+
+    let _writer = lock_env(temp_dir.path(), "app", LockMode::Exclusive, false).unwrap();
+    let output = common::run_muv_command(&["freeze", "app"], &[("MUV_NO_WAIT", "1"), env[0]]);
+    assert_eq!(output.status.code(), Some(14));
+    assert!(lock_env(temp_dir.path(), "app", LockMode::Shared, false).is_err());
+}
+
+#[test]
+fn test_waits_for_busy_env() {
+    let temp_dir = common::setup_test_env();
+    let env_path = common::create_fake_env(temp_dir.path(), "app", "");
+
+    let writer = lock_env(temp_dir.path(), "app", LockMode::Exclusive, false).unwrap();
+    let child = Command::new(env!("CARGO_BIN_EXE_muv"))
+        .args(["delete", "app", "--yes"])
+        .env("MUV_HOME", temp_dir.path())
+        .env("MUV_CONFIG", "/nonexistent/muv/config.toml")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    thread::sleep(Duration::from_millis(300));
+    assert!(env_path.exists(), "deleted while locked");
+    drop(writer);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Environment 'app' is busy (another muv command is using it), waiting...")
+    );
+    assert!(!env_path.exists());
+}
+
+#[test]
+fn test_commands_run_inside_muv_run_reuse_its_lock() {
+    let temp_dir = common::setup_test_env();
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(temp_dir.path(), "app", "");
+    let path = common::path_with(&fake_bin);
+    let env = [
+        ("MUV_HOME", temp_dir.path().to_str().unwrap()),
+        ("PATH", path.as_str()),
+        ("MUV_BIN", env!("CARGO_BIN_EXE_muv")),
+    ];
+
+    // The outer muv holds a shared lock until the shell exits; the inner install
+    // would otherwise never get its exclusive one.
+    let output = common::run_muv_command(
+        &[
+            "run",
+            "app",
+            "--",
+            "sh",
+            "-c",
+            "\"$MUV_BIN\" --no-wait install -e app rich",
+        ],
+        &env,
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        std::fs::read_to_string(temp_dir.path().join("uv.log"))
+            .unwrap()
+            .contains("pip install rich")
+    );
+}
+
+#[test]
+fn test_delete_asks_before_locking() {
+    let temp_dir = common::setup_test_env();
+    common::create_fake_env(temp_dir.path(), "app", "");
+
+    let _reader = lock_env(temp_dir.path(), "app", LockMode::Shared, false).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_muv"))
+        .args(["--no-wait", "delete", "app"])
+        .env("MUV_HOME", temp_dir.path())
+        .env("MUV_CONFIG", "/nonexistent/muv/config.toml")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"n\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    // Declining doesn't get stuck on (or report) the busy environment.
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("User did not confirm deletion."),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+#[test]
+fn test_run_tag_matrix_reports_each_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    for name in ["py311", "py312", "scratch"] {
+        common::create_fake_env(&muv_home, name, "");
+    }
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    for name in ["py311", "py312"] {
+        let output = common::run_muv_command(&["tag", name, "py-matrix"], &env_vars);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let output = common::run_muv_command(&["list"], &env_vars);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- py311 [py-matrix]"));
+    assert!(stdout.contains("- scratch\n"));
+
+    let output = common::run_muv_command(
+        &[
+            "run",
+            "--tag",
+            "py-matrix",
+            "-j",
+            "2",
+            "--",
+            "sh",
+            "-c",
+            r#"echo "hello from $MUV_ENV_NAME"; [ "$MUV_ENV_NAME" != py312 ] || exit 3"#,
+        ],
+        &env_vars,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[py311] hello from py311"));
+    assert!(stdout.contains("[py312] hello from py312"));
+    assert!(!stdout.contains("scratch"));
+
+    let summary: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Environment"))
+        .collect();
+    assert_eq!(summary.len(), 3, "{}", stdout);
+    assert!(summary[1].starts_with("py311") && summary[1].contains("pass"));
+    assert!(summary[2].starts_with("py312") && summary[2].contains("FAIL"));
+    assert!(summary[2].contains(" 3 "));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed in 1 of 2"));
+}
+
+#[test]
+fn test_run_all_succeeds_in_every_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    for name in ["a", "b"] {
+        common::create_fake_env(&muv_home, name, "");
+    }
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    let output = common::run_muv_command(&["run", "--all", "--", "true"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("pass").count(), 2, "{}", stdout);
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+// A PATH without uv on it.
+const PATH_WITHOUT_UV: &str = "/usr/bin:/bin";
+
+// Helper to write a fake interpreter that logs its arguments to `dir/python.log`.
+// `-m venv <path>` creates a skeleton environment whose `bin/python` is this same
+// script, so later `-m pip` calls are logged too; `-m pip freeze` prints
+// `.fake-freeze` from the environment.
+fn install_fake_python(dir: &Path) -> PathBuf {
+    let python_path = dir.join("fake-python");
+    let script = format!(
+        r#"#!/bin/sh
+echo "$@" >> "{log}"
+case "$1 $2 $3" in
+    "-m venv "*)
+        mkdir -p "$3/bin" "$3/lib/python3.12/site-packages"
+        echo "home = /usr/bin" > "$3/pyvenv.cfg"
+        cp "$0" "$3/bin/python" ;;
+    "-m pip freeze") cat "$(dirname "$0")/../.fake-freeze" 2>/dev/null ;;
+    *) exit 0 ;;
+esac
+"#,
+        log = dir.join("python.log").display()
+    );
+    fs::write(&python_path, script).expect("Failed to write fake python");
+    fs::set_permissions(&python_path, fs::Permissions::from_mode(0o755))
+        .expect("Failed to make fake python executable");
+    python_path
+}
+
+#[test]
+fn test_commands_without_backend_work_without_uv() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    common::create_fake_env(temp_dir.path(), "app", "");
+
+    let output = common::run_muv_command(
+        &["list"],
+        &[("MUV_HOME", muv_home), ("PATH", PATH_WITHOUT_UV)],
+    );
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("app"));
+}
+
+#[test]
+fn test_missing_uv_suggests_pip_backend() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+
+    let output = common::run_muv_command(
+        &["create", "app", "click"],
+        &[("MUV_HOME", muv_home), ("PATH", PATH_WITHOUT_UV)],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("'uv' is not installed"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(stderr.contains("--backend pip"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_pip_backend_uses_venv_and_pip() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    let python = install_fake_python(temp_dir.path());
+    let python = python.to_str().unwrap();
+
+    let output = common::run_muv_command(
+        &[
+            "--backend",
+            "pip",
+            "create",
+            "app",
+            "--python",
+            python,
+            "click",
+        ],
+        &[("MUV_HOME", muv_home), ("PATH", PATH_WITHOUT_UV)],
+    );
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(using pip)"));
+
+    let env_path = temp_dir.path().join("envs").join("app");
+    let log = fs::read_to_string(temp_dir.path().join("python.log")).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert!(lines[0].starts_with(&format!(
+        "-m venv {}/",
+        env_path.parent().unwrap().display()
+    )));
+    assert!(lines[0].ends_with("/app"));
+    assert!(env_path.join("pyvenv.cfg").exists());
+    assert!(lines.contains(&"-m pip install click"), "log: {}", log);
+}
+
+#[test]
+fn test_backend_from_environment_variable() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    let python = install_fake_python(temp_dir.path());
+
+    let output = common::run_muv_command(
+        &[
+            "create",
+            "app",
+            "--python",
+            python.to_str().unwrap(),
+            "click",
+        ],
+        &[
+            ("MUV_HOME", muv_home),
+            ("PATH", PATH_WITHOUT_UV),
+            ("MUV_BACKEND", "pip"),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = fs::read_to_string(temp_dir.path().join("python.log")).unwrap();
+    assert!(log.starts_with("-m venv "));
+}
+
+#[test]
+fn test_pip_backend_rejects_version_specifiers() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+
+    let output = common::run_muv_command(
+        &[
+            "--backend",
+            "pip",
+            "create",
+            "app",
+            "--python",
+            ">=3.10",
+            "click",
+        ],
+        &[("MUV_HOME", muv_home), ("PATH", PATH_WITHOUT_UV)],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot pick an interpreter"));
+}
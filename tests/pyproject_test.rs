@@ -0,0 +1,56 @@
+use muv::MuvError;
+use muv::pyproject::{PyProject, python_satisfies};
+use std::fs;
+
+const PYPROJECT: &str = r#"
+[project]
+name = "demo"
+requires-python = ">=3.10"
+dependencies = ["requests>=2"]
+
+[project.optional-dependencies]
+CLI = ["click"]
+docs = ["mkdocs"]
+
+[dependency-groups]
+test = ["pytest"]
+dev = [{ include-group = "test" }, "ruff"]
+"#;
+
+#[test]
+fn test_extras_and_groups() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pyproject.toml");
+    fs::write(&path, PYPROJECT).unwrap();
+
+    let pyproject = PyProject::load(&path).unwrap();
+    assert_eq!(pyproject.dependencies(), ["requests>=2"]);
+    assert_eq!(pyproject.requires_python(), Some(">=3.10"));
+    assert_eq!(pyproject.extra("cli").unwrap(), ["click"]);
+    assert_eq!(pyproject.extra_names(), ["CLI", "docs"]);
+    assert_eq!(pyproject.group("dev").unwrap(), ["pytest", "ruff"]);
+    assert!(pyproject.extra("missing").is_err());
+    assert!(pyproject.group("missing").is_err());
+    assert_eq!(pyproject.root, temp_dir.path());
+}
+
+#[test]
+fn test_malformed_toml_reports_path() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pyproject.toml");
+    fs::write(&path, "[project\nname = ").unwrap();
+
+    let err = PyProject::load(&path).unwrap_err();
+    assert!(matches!(err, MuvError::TomlParseError { .. }));
+    assert!(err.to_string().contains(path.to_str().unwrap()));
+}
+
+#[test]
+fn test_python_satisfies() {
+    assert!(python_satisfies("3.11.4", ">=3.9,<3.13"));
+    assert!(!python_satisfies("3.8.10", ">=3.9"));
+    assert!(python_satisfies("3.12.0rc1", "==3.12.*"));
+    assert!(!python_satisfies("3.12.1", "!=3.12.*"));
+    assert!(python_satisfies("3.10.2", "~=3.10"));
+    assert!(!python_satisfies("4.0", "~=3.10"));
+}
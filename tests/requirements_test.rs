@@ -0,0 +1,39 @@
+use muv::requirements::{self, normalize_name, parse_requirement};
+
+#[test]
+fn test_normalize_name_pep503() {
+    assert_eq!(normalize_name("Flask_Login"), "flask-login");
+    assert_eq!(normalize_name("zope.interface"), "zope-interface");
+    assert_eq!(normalize_name("Some__weird-._Name"), "some-weird-name");
+}
+
+#[test]
+fn test_parse_requirement_lines() {
+    let req = parse_requirement("requests[socks] == 2.31.0 ; python_version > '3.7'").unwrap();
+    assert_eq!(req.name, "requests");
+    assert_eq!(req.pinned_version(), Some("2.31.0"));
+
+    let req = parse_requirement("numpy>=1.24,<2  # comment").unwrap();
+    assert_eq!(req.specifier, ">=1.24,<2");
+    assert_eq!(req.pinned_version(), None);
+
+    assert!(parse_requirement("# just a comment").is_none());
+    assert!(parse_requirement("-r other.txt").is_none());
+    assert!(parse_requirement("   ").is_none());
+}
+
+#[test]
+fn test_diff_requirements() {
+    let from = requirements::parse_requirements("Flask==2.0.0\nrequests==2.28.0\nsix==1.16.0\n");
+    let to = requirements::parse_requirements("flask==3.0.0\nRequests==2.28.0\nrich==13.7.0\n");
+
+    let diff = requirements::diff_requirements(&from, &to);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].name, "rich");
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].name, "six");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].name, "flask");
+    assert_eq!(diff.changed[0].from, "2.0.0");
+    assert_eq!(diff.changed[0].to, "3.0.0");
+}
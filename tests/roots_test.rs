@@ -0,0 +1,121 @@
+mod common;
+
+use muv::roots::{EnvRoot, parse_envs_path};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn make_env(root: &Path, name: &str) -> PathBuf {
+    let env_path = root.join(name);
+    fs::create_dir_all(env_path.join("bin")).unwrap();
+    fs::write(env_path.join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+    env_path
+}
+
+#[test]
+fn test_parse_envs_path() {
+    let roots = parse_envs_path(OsStr::new("me=/home/me/envs:/shared/team")).unwrap();
+    assert_eq!(
+        roots,
+        vec![
+            EnvRoot {
+                name: "me".to_string(),
+                path: PathBuf::from("/home/me/envs"),
+            },
+            EnvRoot {
+                name: "team".to_string(),
+                path: PathBuf::from("/shared/team"),
+            },
+        ]
+    );
+
+    let err = parse_envs_path(OsStr::new("/a/envs:/b/envs")).unwrap_err();
+    assert!(err.to_string().contains("more than one root named 'envs'"));
+}
+
+#[test]
+fn test_lookup_and_list_across_roots() {
+    let temp_dir = common::setup_test_env();
+    let me = temp_dir.path().join("me");
+    let team = temp_dir.path().join("team");
+    make_env(&me, "tool");
+    make_env(&team, "tool");
+    let shared = make_env(&team, "shared");
+    let envs_path = format!("me={}:team={}", me.display(), team.display());
+    let env = [
+        ("MUV_HOME", temp_dir.path().to_str().unwrap()),
+        ("MUV_ENVS_PATH", envs_path.as_str()),
+    ];
+
+    // Earlier roots shadow later ones.
+    let output = common::run_muv_command(&["path", "tool"], &env);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        me.join("tool").display().to_string()
+    );
+    let output = common::run_muv_command(&["path", "shared"], &env);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        shared.display().to_string()
+    );
+
+    let list =
+        String::from_utf8_lossy(&common::run_muv_command(&["list"], &env).stdout).into_owned();
+    assert!(list.contains("- shared  (team)\n"), "{}", list);
+    assert!(list.contains("- tool  (me)\n"), "{}", list);
+    assert!(
+        list.contains("- tool  (team, shadowed by 'me')\n"),
+        "{}",
+        list
+    );
+
+    // Commands find environments by name, so a shadowed one can't be used even
+    // when it's the active one.
+    let shadowed = team.join("tool");
+    let mut active = env.to_vec();
+    active.extend([
+        ("VIRTUAL_ENV", shadowed.to_str().unwrap()),
+        ("MUV_ENV_NAME", "tool"),
+    ]);
+    let output = common::run_muv_command(&["freeze"], &active);
+    assert_eq!(output.status.code(), Some(15));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is shadowed by"), "{}", stderr);
+}
+
+#[test]
+fn test_create_in_root() {
+    let temp_dir = common::setup_test_env();
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let me = temp_dir.path().join("me");
+    let team = temp_dir.path().join("team");
+    make_env(&me, "mine");
+    let envs_path = format!("me={}:team={}", me.display(), team.display());
+    let path = common::path_with(&fake_bin);
+    let env = [
+        ("MUV_HOME", temp_dir.path().to_str().unwrap()),
+        ("MUV_ENVS_PATH", envs_path.as_str()),
+        ("PATH", path.as_str()),
+    ];
+
+    let output = common::run_muv_command(&["create", "--root", "team", "lib", "click"], &env);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(team.join("lib").join("pyvenv.cfg").exists());
+
+    // New environments go into the first root by default.
+    let output = common::run_muv_command(&["create", "app", "click"], &env);
+    assert!(output.status.success());
+    assert!(me.join("app").join("pyvenv.cfg").exists());
+
+    // A new environment that an earlier root would hide is refused.
+    let output = common::run_muv_command(&["create", "--root", "team", "mine", "click"], &env);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("would be hidden"));
+
+    let output = common::run_muv_command(&["create", "--root", "nope", "x", "click"], &env);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown root 'nope'"));
+}
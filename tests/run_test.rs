@@ -0,0 +1,240 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+#[test]
+fn test_run_activates_environment() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "");
+    common::write_env_script(
+        &env_path,
+        "show-env",
+        r#"echo "venv=$VIRTUAL_ENV"
+echo "name=$MUV_ENV_NAME"
+echo "pythonhome=${PYTHONHOME-unset}"
+echo "first-path=$(echo "$PATH" | cut -d: -f1)"
+echo "foo=$FOO"
+echo "cwd=$(pwd)""#,
+    );
+    let work_dir = temp_dir.path().join("work");
+    fs::create_dir_all(&work_dir).unwrap();
+
+    let output = common::run_muv_command(
+        &[
+            "run",
+            "app",
+            "--cwd",
+            work_dir.to_str().unwrap(),
+            "--env",
+            "FOO=bar=baz",
+            "--",
+            "show-env",
+        ],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+            ("PYTHONHOME", "/somewhere/else"),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains(&format!("venv={}", env_path.display())));
+    assert!(stdout.contains("name=app"));
+    assert!(stdout.contains("pythonhome=unset"));
+    assert!(stdout.contains(&format!("first-path={}", env_path.join("bin").display())));
+    assert!(stdout.contains("foo=bar=baz"));
+    assert!(stdout.contains(&format!("cwd={}", work_dir.display())));
+}
+
+#[test]
+fn test_run_prefers_env_bin_over_system_tools() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "");
+    // A `pip` on the outer PATH that must not be picked up.
+    fs::write(fake_bin.join("pip"), "#!/bin/sh\necho system-pip\n").unwrap();
+    fs::set_permissions(fake_bin.join("pip"), fs::Permissions::from_mode(0o755)).unwrap();
+    common::write_env_script(&env_path, "pip", "echo env-pip \"$@\"");
+
+    let output = common::run_muv_command(
+        &["run", "app", "--", "pip", "install", "x"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("env-pip install x"));
+    assert!(!stdout.contains("system-pip"));
+}
+
+#[test]
+fn test_run_propagates_exit_code() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "app", "");
+    let env_vars = [
+        ("MUV_HOME", muv_home.to_str().unwrap()),
+        ("PATH", &common::path_with(&fake_bin)),
+    ];
+
+    let output = common::run_muv_command(&["run", "app", "--", "sh", "-c", "exit 7"], &env_vars);
+    assert_eq!(output.status.code(), Some(7));
+
+    let output = common::run_muv_command(
+        &[
+            "run",
+            "app",
+            "--exec",
+            "--",
+            "sh",
+            "-c",
+            "echo replaced; exit 3",
+        ],
+        &env_vars,
+    );
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "replaced");
+}
+
+#[test]
+fn test_run_mirrors_signal_termination() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "app", "");
+
+    let output = common::run_muv_command(
+        &["run", "app", "--", "sh", "-c", "kill -TERM $$"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert_eq!(output.status.signal(), Some(15));
+}
+
+#[test]
+fn test_run_forwards_sigterm_to_child() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "");
+    let marker = temp_dir.path().join("got-term");
+    common::write_env_script(
+        &env_path,
+        "long-task",
+        &format!(
+            "trap 'touch {}; exit 42' TERM\nwhile true; do sleep 0.1; done",
+            marker.display()
+        ),
+    );
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_muv"))
+        .args(["run", "app", "--", "long-task"])
+        .env("MUV_HOME", &muv_home)
+        .env("PATH", common::path_with(&fake_bin))
+        .spawn()
+        .expect("Failed to start muv");
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    std::process::Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .expect("Failed to send SIGTERM");
+
+    let status = child.wait().expect("Failed to wait for muv");
+    assert_eq!(status.code(), Some(42));
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_run_with_uses_cached_overlay() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "requests==2.31.0\n");
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+    let show = r#"echo "venv=$VIRTUAL_ENV"; echo "path=$PATH"; cat "${PYTHONPATH%%:*}/_muv_base.pth"; "$MUV" freeze"#;
+
+    let mut show_env = env_vars.to_vec();
+    show_env.push(("MUV", env!("CARGO_BIN_EXE_muv")));
+    let output = common::run_muv_command(
+        &["run", "app", "--with", "Rich", "--", "sh", "-c", show],
+        &show_env,
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    // The overlay comes first, but the base environment stays the active one, so
+    // muv commands run by the command still work.
+    let overlays_dir = muv_home.join("overlays");
+    assert!(stdout.contains(&format!("venv={}\n", env_path.display())));
+    assert!(stdout.contains(&format!("path={}", overlays_dir.display())));
+    assert!(stdout.contains("requests==2.31.0"));
+    assert!(
+        stdout.contains(
+            env_path
+                .join("lib/python3.12/site-packages")
+                .to_str()
+                .unwrap()
+        )
+    );
+
+    // Same packages (modulo name normalization) reuse the overlay.
+    let output =
+        common::run_muv_command(&["run", "app", "--with", "rich", "--", "true"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert_eq!(log.lines().filter(|l| l.starts_with("venv")).count(), 1);
+    let install = log
+        .lines()
+        .find(|l| l.starts_with("pip install"))
+        .expect("overlay packages were not installed");
+    assert!(install.starts_with("pip install Rich -c "));
+    assert!(install.ends_with("base-constraints.txt"));
+
+    // The base environment is untouched.
+    assert_eq!(
+        fs::read_to_string(env_path.join(".fake-freeze")).unwrap(),
+        "requests==2.31.0\n"
+    );
+
+    // Once the base environment changes, the overlay is built again against it.
+    fs::write(env_path.join(".fake-freeze"), "requests==2.32.0\n").unwrap();
+    let output =
+        common::run_muv_command(&["run", "app", "--with", "rich", "--", "true"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert_eq!(log.lines().filter(|l| l.starts_with("venv")).count(), 2);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use muv::script::{ScriptMetadata, parse_script_metadata, script_env_key};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_parse_script_metadata() {
+    let source = r#"#!/usr/bin/env -S muv run --script
+# /// script
+# requires-python = ">=3.11"
+# dependencies = [
+#   "requests<3",
+#   "rich",
+# ]
+# ///
+import requests
+"#;
+    let metadata = parse_script_metadata(source, Path::new("tool.py")).unwrap();
+    assert_eq!(metadata.requires_python.as_deref(), Some(">=3.11"));
+    assert_eq!(metadata.dependencies, ["requests<3", "rich"]);
+
+    let reordered = ScriptMetadata {
+        requires_python: Some(">=3.11".into()),
+        dependencies: vec!["rich".into(), "requests<3".into()],
+    };
+    assert_eq!(script_env_key(&metadata), script_env_key(&reordered));
+    assert_ne!(
+        script_env_key(&metadata),
+        script_env_key(&ScriptMetadata::default())
+    );
+
+    assert_eq!(
+        parse_script_metadata("print('hi')\n", Path::new("plain.py")).unwrap(),
+        ScriptMetadata::default()
+    );
+    assert!(
+        parse_script_metadata("# /// script\n# dependencies = []\n", Path::new("x.py")).is_err()
+    );
+}
+
+#[test]
+fn test_run_script_uses_cached_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    // The fake environment's `python` runs scripts with sh, so the body is shell.
+    let script = temp_dir.path().join("tool.py");
+    fs::write(
+        &script,
+        "# /// script\n# requires-python = \">=3.12\"\n# dependencies = [\"rich\"]\n# ///\necho \"args: $*\"\necho \"env: $VIRTUAL_ENV\"\n",
+    )
+    .unwrap();
+    let script = script.to_str().unwrap();
+
+    for _ in 0..2 {
+        let output =
+            common::run_muv_command(&["run", "--script", script, "--verbose", "x"], &env_vars);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("args: --verbose x"), "{}", stdout);
+        assert!(
+            stdout.contains(&format!(
+                "env: {}",
+                muv_home.join("script-envs").join("script-").display()
+            )),
+            "{}",
+            stdout
+        );
+    }
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    let venvs: Vec<&str> = log.lines().filter(|l| l.starts_with("venv")).collect();
+    assert_eq!(venvs.len(), 1, "{}", log);
+    assert!(venvs[0].starts_with("venv --python >=3.12 "));
+    assert_eq!(log.lines().filter(|l| *l == "pip install rich").count(), 1);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+
+// A fake uv whose `venv` writes a console script, an activation script and a native
+// executable with the environment's absolute path in them, and whose `pip install`
+// runs `install`.
+fn fake_uv(dir: &Path, install: &str) -> PathBuf {
+    let bin_dir = dir.join("staging-bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let uv = bin_dir.join("uv");
+    fs::write(
+        &uv,
+        format!(
+            r#"#!/bin/sh
+case "$1 $2" in
+    "--version "*) echo "uv 0.0.0-fake" ;;
+    "venv "*)
+        for target in "$@"; do :; done
+        mkdir -p "$target/bin"
+        echo "home = /usr/bin" > "$target/pyvenv.cfg"
+        printf '#!%s/bin/python\nprint("%s")\n' "$target" "$target" > "$target/bin/tool"
+        printf 'VIRTUAL_ENV=%s\n' "$target" > "$target/bin/activate"
+        printf '\177ELF\377%s\000' "$target" > "$target/bin/native" ;;
+    "pip install") {install} ;;
+esac
+"#
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&uv, fs::Permissions::from_mode(0o755)).unwrap();
+    bin_dir
+}
+
+fn create(muv_home: &Path, bin_dir: &Path, extra: &[&str]) -> std::process::Output {
+    let mut args = vec!["create", "app"];
+    args.extend_from_slice(extra);
+    args.push("click");
+    common::run_muv_command(
+        &args,
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(bin_dir)),
+        ],
+    )
+}
+
+fn staged_envs(muv_home: &Path) -> Vec<PathBuf> {
+    let staging = muv_home.join("envs").join(".muv-staging");
+    let Ok(builds) = fs::read_dir(staging) else {
+        return Vec::new();
+    };
+    builds
+        .flat_map(|build| fs::read_dir(build.unwrap().path()).unwrap())
+        .map(|env| env.unwrap().path())
+        .collect()
+}
+
+#[test]
+fn test_failed_create_leaves_nothing_behind() {
+    let temp_dir = common::setup_test_env();
+    let failing = fake_uv(temp_dir.path(), "echo 'network down' >&2; exit 1");
+
+    let output = create(temp_dir.path(), &failing, &[]);
+    assert!(!output.status.success());
+    assert!(!temp_dir.path().join("envs").join("app").exists());
+    assert!(staged_envs(temp_dir.path()).is_empty());
+
+    // So trying again just works.
+    let working = fake_uv(temp_dir.path(), "exit 0");
+    let output = create(temp_dir.path(), &working, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let env_path = temp_dir.path().join("envs").join("app");
+    // Scripts point at the final location, not the staging directory, but only in
+    // their `#!` line.
+    let tool = fs::read_to_string(env_path.join("bin").join("tool")).unwrap();
+    assert!(
+        tool.starts_with(&format!("#!{}/bin/python\n", env_path.display())),
+        "{}",
+        tool
+    );
+    assert!(tool.contains(".muv-staging"), "{}", tool);
+    assert_eq!(
+        fs::read_to_string(env_path.join("bin").join("activate")).unwrap(),
+        format!("VIRTUAL_ENV={}\n", env_path.display())
+    );
+    // Binaries are left alone.
+    let native = fs::read(env_path.join("bin").join("native")).unwrap();
+    assert!(native.starts_with(b"\x7fELF\xff"));
+    assert!(String::from_utf8_lossy(&native).contains(".muv-staging"));
+    assert!(staged_envs(temp_dir.path()).is_empty());
+}
+
+#[test]
+fn test_keep_on_failure() {
+    let temp_dir = common::setup_test_env();
+    let failing = fake_uv(temp_dir.path(), "exit 1");
+
+    let output = create(temp_dir.path(), &failing, &["--keep-on-failure"]);
+    assert!(!output.status.success());
+    assert!(!temp_dir.path().join("envs").join("app").exists());
+    let kept = staged_envs(temp_dir.path());
+    assert_eq!(kept.len(), 1);
+    assert!(kept[0].join("pyvenv.cfg").exists());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "Kept the partly built environment at {}",
+        kept[0].display()
+    )));
+
+    // The kept build isn't an environment.
+    let output = common::run_muv_command(
+        &["list"],
+        &[("MUV_HOME", temp_dir.path().to_str().unwrap())],
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No environments found"));
+}
+
+#[test]
+fn test_interrupted_create_cleans_up() {
+    let temp_dir = common::setup_test_env();
+    // The install itself succeeds, but muv was told to stop meanwhile.
+    let interrupting = fake_uv(temp_dir.path(), "kill -TERM $PPID; exit 0");
+
+    let output = create(temp_dir.path(), &interrupting, &[]);
+    assert_eq!(output.status.signal(), Some(libc::SIGTERM));
+    assert!(!temp_dir.path().join("envs").join("app").exists());
+    assert!(staged_envs(temp_dir.path()).is_empty());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+fn run(muv_home: &Path, args: &[&str]) -> std::process::Output {
+    common::run_muv_command(args, &[("MUV_HOME", muv_home.to_str().unwrap())])
+}
+
+fn stdout(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_delete_and_restore() {
+    let temp_dir = common::setup_test_env();
+    let home = temp_dir.path();
+    let env_path = common::create_fake_env(home, "app", "click==8.1.7\n");
+
+    let output = run(home, &["delete", "app", "--yes"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Bring it back with 'muv restore app'"));
+    assert!(!env_path.exists());
+
+    let list = stdout(&run(home, &["trash", "list"]));
+    assert!(list.contains("- app  deleted "), "{}", list);
+    assert!(list.contains(&format!("from {}", env_path.display())));
+
+    let output = run(home, &["restore", "app"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(env_path.join(".fake-freeze")).unwrap(),
+        "click==8.1.7\n"
+    );
+    assert!(stdout(&run(home, &["trash", "list"])).contains("The trash is empty."));
+
+    let output = run(home, &["restore", "app"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No environment named 'app' in the trash")
+    );
+}
+
+#[test]
+fn test_restore_picks_latest_copy_and_never_overwrites() {
+    let temp_dir = common::setup_test_env();
+    let home = temp_dir.path();
+    common::create_fake_env(home, "app", "old==1.0\n");
+    assert!(run(home, &["delete", "app", "--yes"]).status.success());
+    let env_path = common::create_fake_env(home, "app", "new==2.0\n");
+    assert!(run(home, &["delete", "app", "--yes"]).status.success());
+
+    // A new environment with the same name is in the way.
+    common::create_fake_env(home, "app", "");
+    let output = run(home, &["restore", "app"]);
+    assert_eq!(output.status.code(), Some(4));
+    fs::remove_dir_all(&env_path).unwrap();
+
+    let output = run(home, &["restore", "app"]);
+    assert!(stdout(&output).contains("An older copy of 'app' is still in the trash."));
+    assert_eq!(
+        fs::read_to_string(env_path.join(".fake-freeze")).unwrap(),
+        "new==2.0\n"
+    );
+}
+
+#[test]
+fn test_permanent_delete_and_empty() {
+    let temp_dir = common::setup_test_env();
+    let home = temp_dir.path();
+    common::create_fake_env(home, "gone", "");
+    common::create_fake_env(home, "old", "");
+    common::create_fake_env(home, "recent", "");
+
+    assert!(
+        run(home, &["delete", "gone", "--yes", "--permanent"])
+            .status
+            .success()
+    );
+    assert!(run(home, &["delete", "old", "--yes"]).status.success());
+    assert!(run(home, &["delete", "recent", "--yes"]).status.success());
+
+    // Backdate the deletion of 'old' by a day.
+    let entries: serde_json::Value =
+        serde_json::from_slice(&run(home, &["--json", "trash", "list"]).stdout).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+    let old = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["name"] == "old")
+        .unwrap();
+    let info_path = home
+        .join("trash")
+        .join(old["id"].as_str().unwrap())
+        .join("trash.json");
+    let mut info: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&info_path).unwrap()).unwrap();
+    info["deleted_at"] = (old["deleted_at"].as_u64().unwrap() - 86_400).into();
+    fs::write(&info_path, info.to_string()).unwrap();
+
+    let output = run(home, &["trash", "empty", "--older-than", "12h"]);
+    assert!(stdout(&output).contains("Removed 1 environment(s) from the trash; 1 left."));
+    let list = stdout(&run(home, &["trash", "list"]));
+    assert!(list.contains("- recent"), "{}", list);
+    assert!(!list.contains("- old"), "{}", list);
+
+    let output = run(home, &["trash", "empty"]);
+    assert!(stdout(&output).contains("Removed 1 environment(s) from the trash; 0 left."));
+}
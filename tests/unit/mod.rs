@@ -1,8 +1,4 @@
-#[path = "../mock/mod.rs"]
-pub mod mock;
-
 // Import test modules
 mod utils_test;
 mod error_test;
-mod mock_test;
 mod cli_test;
\ No newline at end of file
@@ -0,0 +1,101 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_outdated_lists_newer_versions() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "requests==2.28.0\n");
+    fs::write(
+        env_path.join(".fake-outdated"),
+        r#"[{"name":"requests","version":"2.28.0","latest_version":"2.31.0","latest_filetype":"wheel"}]"#,
+    )
+    .unwrap();
+
+    let output = common::run_muv_command(
+        &["outdated", "app", "--find-links", "/tmp/wheels"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("requests"));
+    assert!(stdout.contains("2.31.0"));
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert!(log.contains("pip list --outdated --format json --find-links /tmp/wheels"));
+}
+
+#[test]
+fn test_upgrade_reports_changes() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "requests==2.28.0\n");
+    fs::write(
+        env_path.join(".fake-after-install"),
+        "requests==2.31.0\ncharset-normalizer==3.3.2\n",
+    )
+    .unwrap();
+
+    let output = common::run_muv_command(
+        &["upgrade", "app", "requests"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("~ requests 2.28.0 -> 2.31.0"));
+    assert!(stdout.contains("+ charset-normalizer 3.3.2"));
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert!(log.contains("pip install --upgrade requests"));
+}
+
+#[test]
+fn test_upgrade_dry_run_does_not_install() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "app", "Requests==2.28.0\nsix==1.16.0\n");
+    fs::write(
+        env_path.join(".fake-outdated"),
+        r#"[{"name":"requests","version":"2.28.0","latest_version":"2.31.0"}]"#,
+    )
+    .unwrap();
+
+    let output = common::run_muv_command(
+        &["upgrade", "app", "--all", "--dry-run"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(stdout.contains("requests 2.28.0 -> 2.31.0"));
+
+    let log = fs::read_to_string(temp_dir.path().join("uv.log")).unwrap();
+    assert!(!log.contains("pip install"));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_use_binds_directory_tree_to_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let env_path = common::create_fake_env(&muv_home, "proj", "");
+    let project_dir = temp_dir.path().join("project");
+    let nested_dir = project_dir.join("src").join("pkg");
+    fs::create_dir_all(&nested_dir).unwrap();
+    let path = common::path_with(&fake_bin);
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+
+    let output = common::run_muv_command_in(&project_dir, &["use", "proj"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(project_dir.join(".muv-env")).unwrap(),
+        "proj\n"
+    );
+
+    let output = common::run_muv_command_in(&nested_dir, &["path"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        env_path.to_str().unwrap()
+    );
+
+    let output = common::run_muv_command_in(
+        &nested_dir,
+        &["run", "--", "sh", "-c", "echo $MUV_ENV_NAME"],
+        &env_vars,
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "proj");
+
+    let output = common::run_muv_command_in(&nested_dir, &["info"], &env_vars);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Name:        proj"));
+    assert!(stdout.contains(".muv-env"));
+}
+
+#[test]
+fn test_explicit_name_takes_precedence_over_muv_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    common::create_fake_env(&muv_home, "proj", "");
+    let other_path = common::create_fake_env(&muv_home, "other", "");
+    fs::write(temp_dir.path().join(".muv-env"), "proj\n").unwrap();
+
+    let output = common::run_muv_command_in(
+        temp_dir.path(),
+        &["path", "other"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        other_path.to_str().unwrap()
+    );
+}
+
+#[test]
+fn test_use_rejects_unknown_env() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+
+    let output = common::run_muv_command_in(
+        temp_dir.path(),
+        &["use", "missing"],
+        &[
+            ("MUV_HOME", muv_home.to_str().unwrap()),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(!temp_dir.path().join(".muv-env").exists());
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use muv::error::MuvError;
+use muv::uv_errors::classify;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+fn fallback() -> String {
+    "uv pip install failed with status: 1".to_string()
+}
+
+#[test]
+fn test_classify_no_solution() {
+    let stderr = "  × No solution found when resolving dependencies:
+  ╰─▶ Because there is no version of requests==99.0 and you require requests==99.0, we
+      can conclude that your requirements are unsatisfiable.
+";
+    match classify(stderr, fallback()) {
+        MuvError::ResolutionFailed(explanation) => {
+            assert!(explanation.starts_with("Because there is no version of requests==99.0"));
+            assert!(explanation.ends_with("your requirements are unsatisfiable."));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_classify_missing_interpreter() {
+    let stderr = "error: No interpreter found for Python 3.99 in virtual environments, managed installations, or search path\n";
+    match classify(stderr, fallback()) {
+        MuvError::InterpreterNotFound(request) => assert_eq!(request, "3.99"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_classify_network_error() {
+    let stderr = "error: Failed to fetch: `https://pypi.org/simple/requests/`
+  Caused by: Request failed after 3 retries
+  Caused by: error sending request for url (https://pypi.org/simple/requests/)
+  Caused by: dns error: failed to lookup address information
+";
+    assert!(matches!(
+        classify(stderr, fallback()),
+        MuvError::NetworkError(_)
+    ));
+
+    let offline = "  × No solution found when resolving dependencies:
+  ╰─▶ Because requests was not found in the cache and you require requests, we can
+      conclude that your requirements are unsatisfiable.
+
+      hint: Packages were unavailable because the network was disabled.
+";
+    // Offline resolution failures are still reported as resolution failures.
+    assert!(matches!(
+        classify(offline, fallback()),
+        MuvError::ResolutionFailed(_)
+    ));
+}
+
+#[test]
+fn test_classify_build_failure() {
+    let stderr = "  × Failed to build `psycopg2==2.9.9`
+  ├─▶ The build backend returned an error
+  ╰─▶ Call to `setuptools.build_meta.build_wheel` failed (exit status: 1)
+
+      [stderr]
+      Error: pg_config executable not found.
+      ./psycopg/psycopg.h:36:10: fatal error: libpq-fe.h: No such file or directory
+";
+    match classify(stderr, fallback()) {
+        MuvError::BuildFailed { package, detail } => {
+            assert_eq!(package, "psycopg2==2.9.9");
+            assert!(detail.unwrap().contains("libpq-fe.h"));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_classify_unknown_failure() {
+    match classify("\x1b[31merror\x1b[0m: something else\n", fallback()) {
+        MuvError::UvCommandFailed(message) => assert_eq!(message, fallback()),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_uv_failure_is_streamed_and_classified() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().to_str().unwrap();
+    common::create_fake_env(temp_dir.path(), "app", "");
+    let fake_bin = temp_dir.path().join("failing-bin");
+    fs::create_dir_all(&fake_bin).unwrap();
+    let uv = fake_bin.join("uv");
+    fs::write(
+        &uv,
+        r#"#!/bin/sh
+[ "$1" = "--version" ] && exit 0
+echo "  × No solution found when resolving dependencies:" >&2
+echo "  ╰─▶ Because nothing==1.0 was not found in the package registry, we can conclude that your requirements are unsatisfiable." >&2
+exit 1
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(&uv, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = common::run_muv_command(
+        &["install", "-e", "app", "nothing==1.0"],
+        &[
+            ("MUV_HOME", muv_home),
+            ("PATH", &common::path_with(&fake_bin)),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(6));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // uv's own output is passed through...
+    assert!(stderr.contains("  × No solution found when resolving dependencies:"));
+    // ...followed by muv's summary and hint.
+    assert!(
+        stderr.contains(
+            "Error: Could not resolve the requirements: Because nothing==1.0 was not found"
+        )
+    );
+    assert!(stderr.contains("Hint: "));
+}
+
+#[test]
+fn test_uv_gets_a_terminal_when_stderr_is_one() {
+    use std::io::Read;
+    use std::os::fd::FromRawFd;
+    use std::process::{Command, Stdio};
+
+    let temp_dir = common::setup_test_env();
+    common::create_fake_env(temp_dir.path(), "app", "");
+    let fake_bin = temp_dir.path().join("failing-bin");
+    fs::create_dir_all(&fake_bin).unwrap();
+    let uv = fake_bin.join("uv");
+    fs::write(
+        &uv,
+        r#"#!/bin/sh
+[ "$1" = "--version" ] && exit 0
+[ -t 2 ] && printf 'Resolving...\r' >&2
+echo "  × No solution found when resolving dependencies:" >&2
+echo "  ╰─▶ Because nothing==1.0 was not found in the package registry, we can conclude that your requirements are unsatisfiable." >&2
+exit 1
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(&uv, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // Run muv with a pseudo-terminal as its stderr.
+    let (mut reader, mut writer) = (-1, -1);
+    let opened = unsafe {
+        libc::openpty(
+            &mut reader,
+            &mut writer,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(opened, 0);
+    let (mut reader, writer) =
+        unsafe { (fs::File::from_raw_fd(reader), fs::File::from_raw_fd(writer)) };
+    let mut child = Command::new(env!("CARGO_BIN_EXE_muv"))
+        .args(["install", "-e", "app", "nothing==1.0"])
+        .env("MUV_CONFIG", "/nonexistent/muv/config.toml")
+        .env("MUV_HOME", temp_dir.path())
+        .env("PATH", common::path_with(&fake_bin))
+        .stdout(Stdio::null())
+        .stderr(writer)
+        .spawn()
+        .unwrap();
+    let mut captured = Vec::new();
+    let mut buf = [0; 4096];
+    // Reading fails with EIO once muv has exited.
+    while let Ok(n @ 1..) = reader.read(&mut buf) {
+        captured.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(child.wait().unwrap().code(), Some(6));
+    let stderr = String::from_utf8_lossy(&captured);
+    // uv saw a terminal and drew its progress line...
+    assert!(stderr.contains("Resolving...\r"));
+    // ...and its failure is still understood.
+    assert!(
+        stderr.contains(
+            "Error: Could not resolve the requirements: Because nothing==1.0 was not found"
+        )
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_which_reports_providers_owner_and_winner() {
+    let temp_dir = common::setup_test_env();
+    let muv_home = temp_dir.path().join(".muv");
+    let fake_bin = common::install_fake_uv(temp_dir.path());
+    let lab = common::create_fake_env(&muv_home, "lab", "");
+    let scratch = common::create_fake_env(&muv_home, "scratch", "");
+    common::create_fake_env(&muv_home, "empty", "");
+    for env in [&lab, &scratch] {
+        common::write_env_script(env, "jupyter-lab", "true");
+    }
+
+    let site = lab.join("lib/python3.12/site-packages");
+    let dist_info = site.join("jupyterlab-4.1.0.dist-info");
+    fs::create_dir_all(&dist_info).unwrap();
+    fs::write(
+        dist_info.join("RECORD"),
+        "jupyterlab/__init__.py,sha256=abc,10\n../../../bin/jupyter-lab,sha256=def,200\n",
+    )
+    .unwrap();
+    // `scratch` only has entry points metadata.
+    let dist_info = scratch.join("lib/python3.12/site-packages/jupyterlab-3.6.0.dist-info");
+    fs::create_dir_all(&dist_info).unwrap();
+    fs::write(
+        dist_info.join("entry_points.txt"),
+        "[console_scripts]\njupyter-lab = jupyterlab.labapp:main\n",
+    )
+    .unwrap();
+
+    // As if `scratch` were activated.
+    let path = format!(
+        "{}:{}",
+        scratch.join("bin").display(),
+        common::path_with(&fake_bin)
+    );
+    let env_vars = [("MUV_HOME", muv_home.to_str().unwrap()), ("PATH", &path)];
+    let output = common::run_muv_command(&["which", "jupyter-lab"], &env_vars);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(
+        lines[1].starts_with("  lab ") && lines[1].ends_with("(jupyterlab 4.1.0)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        lines[2].starts_with("* scratch ") && lines[2].ends_with("(jupyterlab 3.6.0)"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("empty"));
+    assert!(stdout.contains("currently runs from environment 'scratch'"));
+
+    let output = common::run_muv_command(&["which", "nope"], &env_vars);
+    assert!(!output.status.success());
+}